@@ -0,0 +1,375 @@
+use crate::checkpoint::{Checkpoint, CheckpointEntry, FailedAttempt};
+use crate::config::SectionConfig;
+use crate::used_words::UsedWords;
+use anyhow::Result;
+use crossword_core::{Dictionary, solve_with_iterations, CrosswordPuzzle};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Attempts allowed per requested puzzle when no --max-attempts is given
+pub const DEFAULT_ATTEMPTS_PER_PUZZLE: usize = 10;
+
+/// When a run stops scheduling new attempts
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Budget {
+    pub max_attempts: Option<usize>,
+    /// No new attempt starts after this; attempts already running finish
+    pub time_limit: Option<Duration>,
+}
+
+/// Counts for the end-of-run report
+#[derive(Debug, Clone, Default)]
+pub struct BatchStats {
+    pub attempts: usize,
+    pub succeeded: usize,
+    /// Failed attempts by kind, see `failure_kind`
+    pub failures: BTreeMap<&'static str, usize>,
+    /// Total time of the successful attempts
+    pub solve_time: Duration,
+}
+
+impl BatchStats {
+    pub fn failed(&self) -> usize {
+        self.failures.values().sum()
+    }
+
+    pub fn report(&self) -> String {
+        let mut report = format!("Attempts: {} ({} succeeded, {} failed)", self.attempts, self.succeeded, self.failed());
+        if !self.failures.is_empty() {
+            let kinds: Vec<String> = self.failures.iter().map(|(kind, n)| format!("{} {}", n, kind)).collect();
+            report.push_str(&format!("\nFailures: {}", kinds.join(", ")));
+        }
+        if self.succeeded > 0 {
+            report.push_str(&format!(
+                "\nAverage solve time: {:.1}s",
+                self.solve_time.as_secs_f64() / self.succeeded as f64
+            ));
+        }
+        report
+    }
+}
+
+/// Budget and stats shared by the worker threads of a run
+pub struct BatchState {
+    started: Instant,
+    budget: Budget,
+    stats: Mutex<BatchStats>,
+}
+
+impl BatchState {
+    pub fn new(budget: Budget) -> Self {
+        Self {
+            started: Instant::now(),
+            budget,
+            stats: Mutex::new(BatchStats::default()),
+        }
+    }
+
+    /// Claim the next attempt, or `false` once the budget is spent
+    pub fn try_start(&self) -> bool {
+        if self.budget.time_limit.is_some_and(|limit| self.started.elapsed() >= limit) {
+            return false;
+        }
+        let mut stats = self.stats.lock().unwrap();
+        if self.budget.max_attempts.is_some_and(|max| stats.attempts >= max) {
+            return false;
+        }
+        stats.attempts += 1;
+        true
+    }
+
+    pub fn record_success(&self, elapsed: Duration) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.succeeded += 1;
+        stats.solve_time += elapsed;
+    }
+
+    pub fn record_failure(&self, error: &str) {
+        *self.stats.lock().unwrap().failures.entry(failure_kind(error)).or_default() += 1;
+    }
+
+    pub fn stats(&self) -> BatchStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+/// Everything the sections of one `generate` run share
+pub struct BatchRun<'a> {
+    pub dict: &'a Dictionary,
+    pub seed: u64,
+    pub checkpoint: Mutex<Checkpoint>,
+    pub state: BatchState,
+    /// First attempt number per (section, slot); past failures from a
+    /// resumed checkpoint move it on so their seeds aren't tried again
+    pub next_attempt: HashMap<(usize, usize), usize>,
+    /// Answers used so far, when the book avoids repeating them
+    pub used_words: Option<Mutex<UsedWords>>,
+}
+
+impl BatchRun<'_> {
+    /// Generate a section's `pending` slots in parallel, appending each
+    /// result to the checkpoint as it finishes. A failed slot is retried
+    /// with a new seed until it succeeds or the budget runs out.
+    pub fn generate_section(
+        &self,
+        idx: usize,
+        section: &SectionConfig,
+        pending: &[usize],
+        show_title: bool,
+    ) -> Result<Vec<(usize, CrosswordPuzzle)>> {
+        let heading = if show_title { format!("{}: ", section.title) } else { String::new() };
+        println!("\n{}Generating {} puzzles of size {}x{} in parallel...", heading, pending.len(), section.size, section.size);
+        let pb = ProgressBar::new(section.count as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("=>-"),
+        );
+        pb.set_position((section.count - pending.len()) as u64);
+
+        // Generate puzzles in parallel
+        use rayon::prelude::*;
+        let results: Vec<Option<(usize, CrosswordPuzzle)>> = pending
+            .par_iter()
+            .map(|&slot| {
+                let mut attempt = self.next_attempt.get(&(idx, slot)).copied().unwrap_or(0);
+                while self.state.try_start() {
+                    let puzzle_seed = puzzle_seed(self.seed, idx, slot, attempt);
+                    let mut rng = rand::rngs::StdRng::seed_from_u64(puzzle_seed);
+                    let started = Instant::now();
+                    // Sample against a snapshot, so other threads can record
+                    // their puzzles while this one solves
+                    let used = self.used_words.as_ref().map(|used| used.lock().unwrap().clone());
+                    let generated = generate_crossword(self.dict, section.size, &mut rng, used.as_ref())
+                        .and_then(|puzzle| {
+                            // Another thread may have used up an answer meanwhile
+                            if let Some(ref used) = self.used_words {
+                                used.lock().unwrap().try_add_puzzle(&puzzle).map_err(anyhow::Error::msg)?;
+                            }
+                            Ok(puzzle)
+                        });
+                    match generated {
+                        Ok(puzzle) => {
+                            self.state.record_success(started.elapsed());
+                            self.checkpoint.lock().unwrap().append(&CheckpointEntry {
+                                section: idx,
+                                slot,
+                                size: section.size,
+                                seed: puzzle_seed,
+                                puzzle: puzzle.clone(),
+                            })?;
+                            pb.inc(1);
+                            return Ok(Some((slot, puzzle)));
+                        }
+                        Err(e) => {
+                            let error = e.to_string();
+                            self.state.record_failure(&error);
+                            self.checkpoint.lock().unwrap().append_failure(&FailedAttempt {
+                                section: idx,
+                                slot,
+                                attempt,
+                                error,
+                            })?;
+                            pb.set_message(format!("{} failed attempts", self.state.stats().failed()));
+                            attempt += 1;
+                        }
+                    }
+                }
+                Ok(None)
+            })
+            .collect::<Result<_>>()?;
+
+        let puzzles: Vec<_> = results.into_iter().flatten().collect();
+        pb.finish_with_message(format!("Complete! {} successful, {} not generated",
+                                       puzzles.len(),
+                                       pending.len() - puzzles.len()));
+        Ok(puzzles)
+    }
+}
+
+/// Seed for one generation attempt, derived from the run's seed so a
+/// resumed run regenerates exactly what an uninterrupted one would
+fn puzzle_seed(seed: u64, section: usize, slot: usize, attempt: usize) -> u64 {
+    [section, slot, attempt].iter().fold(seed, |z, &part| {
+        // SplitMix64 finalizer
+        let mut z = (z ^ part as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+}
+
+/// Sample words from the dictionary and solve one grid. With `used`,
+/// answers from earlier puzzles are sampled last or not at all.
+pub fn generate_crossword(
+    dict: &Dictionary,
+    size: usize,
+    rng: &mut impl rand::Rng,
+    used: Option<&UsedWords>,
+) -> Result<CrosswordPuzzle> {
+    let all_words = dict.get_words();
+    
+    // Filter suitable words
+    let suitable: Vec<String> = all_words.iter()
+        .filter(|w| w.len() >= 3 && w.len() <= size)
+        .cloned()
+        .collect();
+    
+    // Group by length
+    let mut by_length: std::collections::HashMap<usize, Vec<String>> = std::collections::HashMap::new();
+    for word in suitable {
+        by_length.entry(word.len()).or_default().push(word);
+    }
+    
+    // Determine max words based on size
+    let max_words = match size {
+        s if s <= 8 => 80,
+        s if s <= 10 => 120,
+        s if s <= 12 => 150,
+        s if s <= 15 => 130,
+        s if s <= 20 => 100,
+        _ => 100,
+    };
+    
+    let mut words = Vec::new();
+    
+    // Select words with length distribution
+    for len in 3..=size.min(15) {
+        if let Some(len_words) = by_length.get_mut(&len) {
+            len_words.shuffle(rng);
+            if let Some(used) = used {
+                used.prefer_unused(len_words);
+            }
+            
+            let proportion = if len <= 5 {
+                0.70
+            } else if len <= 8 {
+                0.25
+            } else {
+                0.05
+            };
+            
+            let count = ((max_words as f32 * proportion) / 4.0) as usize;
+            words.extend(len_words.iter().take(count.max(8)).cloned());
+            
+            if words.len() >= max_words {
+                break;
+            }
+        }
+    }
+    
+    words.truncate(max_words);
+    
+    // Solve the crossword
+    let (placements, elapsed_ms, _num_vars, _num_clauses) = solve_with_iterations(&words, size)
+        .map_err(|e| anyhow::anyhow!("Solver failed: {}", e))?;
+    
+    // Create puzzle
+    let puzzle = CrosswordPuzzle::from_placements(
+        &placements,
+        size,
+        |word| dict.get_clue(word),
+        elapsed_ms,
+    );
+    
+    Ok(puzzle)
+}
+
+/// Generate one puzzle from `seed`, retrying with `seed + 1`, `seed + 2`, ...
+/// until one succeeds or `max_attempts` have failed. Returns the puzzle and
+/// the number of failed attempts.
+pub fn generate_with_retries(
+    dict: &Dictionary,
+    size: usize,
+    seed: u64,
+    max_attempts: usize,
+    used: Option<&UsedWords>,
+) -> Result<(CrosswordPuzzle, usize)> {
+    let mut last_error = None;
+    for attempt in 0..max_attempts {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed.wrapping_add(attempt as u64));
+        match generate_crossword(dict, size, &mut rng, used) {
+            Ok(puzzle) => return Ok((puzzle, attempt)),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match last_error {
+        Some(e) => Err(e.context(format!("No puzzle after {} attempts", max_attempts))),
+        None => anyhow::bail!("No attempts allowed"),
+    }
+}
+
+/// Group generation errors by cause for the report
+pub fn failure_kind(error: &str) -> &'static str {
+    if error.contains("UNSAT") {
+        "unsatisfiable word sample"
+    } else if error.contains("No placements found") {
+        "no words placed"
+    } else if error.contains("No model available") || error.contains("Solver error") {
+        "solver error"
+    } else if error.contains("Repeated answer") {
+        "repeated answer"
+    } else {
+        "other"
+    }
+}
+
+/// Parse a time budget such as `90`, `90s`, `45m` or `2h`
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let (number, unit) = match s.char_indices().find(|(_, c)| !c.is_ascii_digit() && *c != '.') {
+        Some((idx, _)) => s.split_at(idx),
+        None => (s, "s"),
+    };
+    let value: f64 = number.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration: {}. Use e.g. 90s, 45m or 2h", s))?;
+    let seconds = match unit.trim() {
+        "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => anyhow::bail!("Invalid duration: {}. Use e.g. 90s, 45m or 2h", s),
+    };
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| anyhow::anyhow!("Invalid duration: {}. Use e.g. 90s, 45m or 2h", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_budget() {
+        let state = BatchState::new(Budget { max_attempts: Some(3), time_limit: None });
+        assert!(state.try_start());
+        state.record_failure("Solver failed: UNSAT");
+        assert!(state.try_start());
+        state.record_failure("Solver failed: UNSAT");
+        assert!(state.try_start());
+        state.record_success(Duration::from_secs(4));
+        assert!(!state.try_start());
+
+        let stats = state.stats();
+        assert_eq!((stats.attempts, stats.succeeded, stats.failed()), (3, 1, 2));
+        assert_eq!(
+            stats.report(),
+            "Attempts: 3 (1 succeeded, 2 failed)\nFailures: 2 unsatisfiable word sample\nAverage solve time: 4.0s"
+        );
+
+        let expired = BatchState::new(Budget { max_attempts: None, time_limit: Some(Duration::ZERO) });
+        assert!(!expired.try_start());
+
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("1.5h").unwrap(), Duration::from_secs(5400));
+        assert!(parse_duration("2 days").is_err());
+        assert!(parse_duration("99999999999999999999999h").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::{Context, Result};
+use crossword_core::CrosswordPuzzle;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// First line of a checkpoint: what the run was started with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointHeader {
+    pub seed: u64,
+}
+
+/// One finished puzzle. `section` and `slot` say where it goes in the book;
+/// `seed` is the seed it was generated from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointEntry {
+    pub section: usize,
+    pub slot: usize,
+    pub size: usize,
+    pub seed: u64,
+    pub puzzle: CrosswordPuzzle,
+}
+
+/// An attempt that failed, so a resumed run moves on to new seeds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedAttempt {
+    pub section: usize,
+    pub slot: usize,
+    pub attempt: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum CheckpointLine {
+    Puzzle(CheckpointEntry),
+    Failure(FailedAttempt),
+}
+
+/// What a checkpoint holds so far
+#[derive(Debug)]
+pub struct CheckpointContents {
+    pub header: CheckpointHeader,
+    pub puzzles: Vec<CheckpointEntry>,
+    pub failures: Vec<FailedAttempt>,
+}
+
+/// Append-only JSONL file that each puzzle is written to as soon as it is
+/// generated, so an interrupted run can be resumed
+pub struct Checkpoint {
+    path: PathBuf,
+    file: File,
+}
+
+impl Checkpoint {
+    /// Start a new checkpoint. Refuses to overwrite one with puzzles in it.
+    pub fn create(path: &Path, header: &CheckpointHeader) -> Result<Self> {
+        if path.metadata().map(|m| m.len() > 0).unwrap_or(false) {
+            anyhow::bail!(
+                "Checkpoint {} already exists. Pass --resume to continue that run, or delete it to start over",
+                path.display()
+            );
+        }
+        let mut checkpoint = Self::open(path)?;
+        checkpoint.write_line(&serde_json::to_string(header)?)?;
+        Ok(checkpoint)
+    }
+
+    /// Reopen a checkpoint, returning what it holds so far. A missing file
+    /// starts a new checkpoint with `header`.
+    pub fn resume(path: &Path, header: &CheckpointHeader) -> Result<(Self, CheckpointContents)> {
+        if !path.exists() {
+            let contents = CheckpointContents { header: header.clone(), puzzles: Vec::new(), failures: Vec::new() };
+            return Ok((Self::create(path, header)?, contents));
+        }
+
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+        let contents = parse_checkpoint(&text)
+            .with_context(|| format!("Invalid checkpoint {}", path.display()))?;
+
+        let mut checkpoint = Self::open(path)?;
+        if !text.is_empty() && !text.ends_with('\n') {
+            // A run killed mid-write leaves a partial last line; start a fresh one
+            checkpoint.write_line("")?;
+        }
+        Ok((checkpoint, contents))
+    }
+
+    pub fn append(&mut self, entry: &CheckpointEntry) -> Result<()> {
+        self.write_line(&serde_json::to_string(entry)?)
+    }
+
+    pub fn append_failure(&mut self, failure: &FailedAttempt) -> Result<()> {
+        self.write_line(&serde_json::to_string(failure)?)
+    }
+
+    /// Delete the checkpoint once the book is safely written
+    pub fn remove(self) -> Result<()> {
+        std::fs::remove_file(&self.path)
+            .with_context(|| format!("Failed to remove checkpoint {}", self.path.display()))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open checkpoint {}", path.display()))?;
+        Ok(Self { path: path.to_path_buf(), file })
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        self.file.write_all(format!("{}\n", line).as_bytes())
+            .and_then(|_| self.file.flush())
+            .with_context(|| format!("Failed to write checkpoint {}", self.path.display()))
+    }
+}
+
+/// Parse a checkpoint's text. A truncated last line is ignored; any other
+/// bad line is an error.
+fn parse_checkpoint(text: &str) -> Result<CheckpointContents> {
+    let lines: Vec<(usize, &str)> = text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    let Some(&(_, first)) = lines.first() else {
+        anyhow::bail!("Checkpoint is empty");
+    };
+    let header: CheckpointHeader = serde_json::from_str(first)
+        .context("Line 1: invalid header")?;
+
+    let mut contents = CheckpointContents { header, puzzles: Vec::new(), failures: Vec::new() };
+    for (pos, &(idx, line)) in lines.iter().enumerate().skip(1) {
+        match serde_json::from_str(line) {
+            Ok(CheckpointLine::Puzzle(entry)) => contents.puzzles.push(entry),
+            Ok(CheckpointLine::Failure(failure)) => contents.failures.push(failure),
+            Err(_) if pos == lines.len() - 1 && serde_json::from_str::<serde_json::Value>(line).is_err() => {
+                eprintln!("Warning: ignoring incomplete last line {} of checkpoint", idx + 1);
+            }
+            Err(e) => return Err(e).with_context(|| format!("Line {}", idx + 1)),
+        }
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::sample_puzzle;
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let path = std::env::temp_dir().join(format!("crossword-checkpoint-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let puzzle = sample_puzzle(3, |_| "Pet".to_string());
+        let header = CheckpointHeader { seed: 42 };
+
+        let mut checkpoint = Checkpoint::create(&path, &header).unwrap();
+        checkpoint.append(&CheckpointEntry { section: 0, slot: 1, size: 3, seed: 7, puzzle }).unwrap();
+        checkpoint.append_failure(&FailedAttempt { section: 0, slot: 2, attempt: 0, error: "UNSAT".to_string() }).unwrap();
+        drop(checkpoint);
+        assert!(Checkpoint::create(&path, &header).is_err());
+
+        // Simulate a crash part-way through the next line
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str("{\"section\":0,\"slot\":2,\"si");
+        std::fs::write(&path, text).unwrap();
+
+        let (checkpoint, contents) = Checkpoint::resume(&path, &CheckpointHeader { seed: 1 }).unwrap();
+        assert_eq!(contents.header, header);
+        assert_eq!(contents.puzzles.len(), 1);
+        assert_eq!((contents.puzzles[0].slot, contents.puzzles[0].seed), (1, 7));
+        assert_eq!(contents.failures.len(), 1);
+        checkpoint.remove().unwrap();
+    }
+}
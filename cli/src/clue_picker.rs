@@ -0,0 +1,144 @@
+use crossword_core::{BookConfig, Clue, ClueRotation, CrosswordPuzzle, Dictionary};
+
+/// Average width of a character of 11pt body text, in inches
+const CHAR_WIDTH: f32 = 0.075;
+
+/// Baseline skip of 11pt body text, in inches
+const LINE_HEIGHT: f32 = 0.19;
+
+/// Extra space each numbered clue adds, in lines
+const ITEM_SPACING: f32 = 0.4;
+
+/// Puzzle title and Across/Down headings above the clue lists, in inches
+const HEADINGS_HEIGHT: f32 = 1.2;
+
+/// Indent taken by the clue numbers, in inches
+const LABEL_WIDTH: f32 = 0.3;
+
+/// Rough capacity of one clue column on a puzzle's clue page. The two
+/// columns are minipages, so text that doesn't fit runs off the page.
+#[derive(Debug, Clone, Copy)]
+pub struct CluePage {
+    chars_per_line: usize,
+    lines: f32,
+}
+
+impl CluePage {
+    pub fn new(config: &BookConfig) -> Self {
+        let margins = config.margins();
+        let text_width = config.trim_size.width - margins.inner - margins.outer;
+        let text_height = config.trim_size.height - margins.top - margins.bottom;
+        let column_width = text_width * 0.48 - LABEL_WIDTH;
+        Self {
+            chars_per_line: ((column_width / CHAR_WIDTH) as usize).max(1),
+            lines: (text_height - HEADINGS_HEIGHT) / LINE_HEIGHT,
+        }
+    }
+
+    /// Whether a column of these clues fits
+    pub fn fits<'a>(&self, clues: impl IntoIterator<Item = &'a str>) -> bool {
+        let used: f32 = clues.into_iter()
+            .map(|clue| clue.chars().count().div_ceil(self.chars_per_line).max(1) as f32 + ITEM_SPACING)
+            .sum();
+        used <= self.lines
+    }
+}
+
+/// Chooses clues for a book's puzzles in book order. An answer that comes
+/// up again gets a clue the book hasn't used for it, while it has one.
+pub struct CluePicker<'a> {
+    dict: &'a Dictionary,
+    page: CluePage,
+    rotation: ClueRotation,
+}
+
+impl<'a> CluePicker<'a> {
+    pub fn new(dict: &'a Dictionary, config: &BookConfig) -> Self {
+        Self { dict, page: CluePage::new(config), rotation: ClueRotation::new() }
+    }
+
+    /// Count the clues of a puzzle that keeps the clues it has
+    pub fn record(&mut self, puzzle: &CrosswordPuzzle) {
+        for clue in puzzle.across_clues.iter().chain(&puzzle.down_clues) {
+            self.rotation.record(&clue.word, &clue.clue);
+        }
+    }
+
+    /// Re-clue a puzzle from the dictionary and clue database. Answers with
+    /// no clue there keep theirs.
+    pub fn assign(&mut self, puzzle: &mut CrosswordPuzzle) {
+        self.assign_column(&mut puzzle.across_clues);
+        self.assign_column(&mut puzzle.down_clues);
+    }
+
+    /// When the column would run off the page, its longest clues are
+    /// swapped for shorter senses until it fits: unused ones first, then
+    /// any, since a repeated clue beats one that isn't printed
+    fn assign_column(&mut self, clues: &mut [Clue]) {
+        let candidates: Vec<Vec<String>> = clues.iter().map(|clue| self.dict.get_clues(&clue.word)).collect();
+        let mut picks: Vec<String> = clues.iter()
+            .zip(&candidates)
+            .map(|(clue, senses)| self.rotation.pick(&clue.word, senses, false).unwrap_or(&clue.clue).clone())
+            .collect();
+
+        for allow_repeats in [false, true] {
+            if self.page.fits(picks.iter().map(String::as_str)) {
+                break;
+            }
+            let mut longest_first: Vec<usize> = (0..clues.len()).collect();
+            longest_first.sort_by_key(|&idx| std::cmp::Reverse(picks[idx].chars().count()));
+            for idx in longest_first {
+                let senses = &candidates[idx];
+                let shorter = if allow_repeats {
+                    senses.iter().min_by_key(|sense| sense.chars().count())
+                } else {
+                    self.rotation.pick(&clues[idx].word, senses, true)
+                };
+                if let Some(shorter) = shorter.filter(|sense| sense.chars().count() < picks[idx].chars().count()) {
+                    picks[idx] = shorter.clone();
+                }
+                if self.page.fits(picks.iter().map(String::as_str)) {
+                    break;
+                }
+            }
+        }
+
+        for (clue, pick) in clues.iter_mut().zip(picks) {
+            self.rotation.record(&clue.word, &pick);
+            clue.clue = pick;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::sample_puzzle;
+    use crossword_core::ClueTable;
+
+    #[test]
+    fn test_clue_picker() {
+        let long = "A domesticated carnivorous mammal kept as a pet or for catching mice, often with soft fur";
+        let table = format!("CAT\tPurring pet\nCAT\tJazz fan\nCAT\t{}\nOWL\tNight hunter\n", long);
+        let dict = Dictionary::from_source(Box::new(ClueTable::parse(&table)), None);
+        let puzzle = sample_puzzle(3, |word| dict.get_clue(word));
+
+        let config = BookConfig::new("Test".to_string(), 3);
+        let clues_for = |picker: &mut CluePicker, times: usize| -> Vec<String> {
+            (0..times).map(|_| {
+                let mut copy = puzzle.clone();
+                picker.assign(&mut copy);
+                copy.across_clues[0].clue.clone()
+            }).collect()
+        };
+
+        let mut picker = CluePicker::new(&dict, &config);
+        assert_eq!(clues_for(&mut picker, 4), ["Purring pet", "Jazz fan", long, "Purring pet"]);
+
+        // A page with room for one short line repeats a short clue rather
+        // than overflow with the unused long one
+        let mut picker = CluePicker::new(&dict, &config);
+        picker.page = CluePage { chars_per_line: 20, lines: 1.5 };
+        assert_eq!(clues_for(&mut picker, 3), ["Purring pet", "Jazz fan", "Jazz fan"]);
+    }
+}
@@ -0,0 +1,90 @@
+use crossword_core::ClueRecord;
+use anyhow::{Result, Context};
+
+const HEADER: [&str; 5] = ["puzzle", "number", "direction", "answer", "clue"];
+
+/// Write clue records as CSV with a header row, for editing in a spreadsheet
+pub fn write_clues_csv(records: &[ClueRecord]) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(HEADER)?;
+    for record in records {
+        writer.write_record([
+            record.puzzle.to_string(),
+            record.number.to_string(),
+            record.direction.as_str().to_lowercase(),
+            record.answer.clone(),
+            record.clue.clone(),
+        ])?;
+    }
+    writer.into_inner().context("Failed to write CSV")
+}
+
+/// Read clue records from CSV written by `write_clues_csv`. Columns are
+/// matched by header name, so editors may reorder them or add their own.
+pub fn read_clues_csv(data: &[u8]) -> Result<Vec<ClueRecord>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(data);
+
+    let headers = reader.headers().context("Failed to read CSV header")?.clone();
+    let mut columns = [0; HEADER.len()];
+    for (column, name) in columns.iter_mut().zip(HEADER) {
+        *column = headers.iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+            .with_context(|| format!("CSV header has no '{}' column", name))?;
+    }
+    let [puzzle, number, direction, answer, clue] = columns;
+
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row.context("Malformed CSV")?;
+        let line = row.position().map(|p| p.line()).unwrap_or(0);
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let field = |idx: usize| row.get(idx).unwrap_or("").trim();
+
+        records.push(ClueRecord {
+            puzzle: field(puzzle).parse()
+                .with_context(|| format!("Line {}: invalid puzzle '{}'", line, field(puzzle)))?,
+            number: field(number).parse()
+                .with_context(|| format!("Line {}: invalid number '{}'", line, field(number)))?,
+            direction: field(direction).parse()
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("Line {}", line))?,
+            answer: field(answer).to_string(),
+            clue: field(clue).to_string(),
+        });
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::Direction;
+
+    #[test]
+    fn test_clues_csv_round_trip() {
+        let records = vec![ClueRecord {
+            puzzle: 2,
+            number: 14,
+            direction: Direction::Down,
+            answer: "CAT".to_string(),
+            clue: "Pet, \"purrs\"".to_string(),
+        }];
+
+        let csv = write_clues_csv(&records).unwrap();
+        let text = String::from_utf8(csv.clone()).unwrap();
+        assert_eq!(text, "puzzle,number,direction,answer,clue\n2,14,down,CAT,\"Pet, \"\"purrs\"\"\"\n");
+        assert_eq!(read_clues_csv(&csv).unwrap(), records);
+
+        // Reordered and extra columns are fine
+        let edited = "clue,notes,answer,direction,number,puzzle\nFeline,ok,cat,D,14,2\n";
+        assert_eq!(read_clues_csv(edited.as_bytes()).unwrap()[0].clue, "Feline");
+
+        let err = read_clues_csv(b"puzzle,number,direction,answer,clue\n2,x,down,CAT,Pet\n").unwrap_err();
+        assert!(err.to_string().contains("Line 2: invalid number 'x'"));
+    }
+}
@@ -0,0 +1,260 @@
+use crossword_core::{KdpFormat, TrimSize};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Smallest grid that fits a 3-letter word
+const MIN_GRID_SIZE: usize = 3;
+
+/// Book and generation settings read from a TOML file, e.g. `book.toml`.
+/// Every key is optional; flags given on the command line win.
+///
+/// ```toml
+/// title = "Ultimate Crosswords"
+/// author = "Sean Reid"
+/// trim_size = "6x9"
+///
+/// [generate]
+/// seed = 12345
+///
+/// [cover]
+/// template = "paperback-cover.svg"
+///
+/// [[section]]
+/// title = "Warm-up"
+/// intro = "Small grids to get you started."
+/// count = 20
+/// size = 10
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub author: Option<String>,
+    pub publisher: Option<String>,
+    pub edition: Option<String>,
+    pub isbn: Option<String>,
+    pub copyright: Option<String>,
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "kdp_format")]
+    pub kdp_format: Option<KdpFormat>,
+    #[serde(default, deserialize_with = "trim_size")]
+    pub trim_size: Option<TrimSize>,
+    #[serde(default)]
+    pub generate: GenerateSettings,
+    #[serde(default)]
+    pub cover: CoverSettings,
+    /// Puzzles are generated section by section, in order
+    #[serde(default, rename = "section")]
+    pub sections: Vec<SectionConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateSettings {
+    pub count: Option<usize>,
+    pub size: Option<usize>,
+    pub seed: Option<u64>,
+    pub jobs: Option<usize>,
+    pub allowlist: Option<PathBuf>,
+    pub dictionary: Option<PathBuf>,
+    /// oxford, words or tsv
+    pub dictionary_format: Option<String>,
+    /// Usage labels to leave out, e.g. ["archaic", "derog"]
+    pub exclude_labels: Option<Vec<String>>,
+    pub blocklist: Option<PathBuf>,
+    /// Clue database checked before dictionary definitions
+    pub clues: Option<PathBuf>,
+    /// easy, medium or hard
+    pub clue_difficulty: Option<String>,
+    pub max_attempts: Option<usize>,
+    pub max_repeats: Option<usize>,
+    pub used_words: Option<PathBuf>,
+    /// e.g. "90m" or "2h"
+    #[serde(default, deserialize_with = "duration")]
+    pub time_limit: Option<Duration>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CoverSettings {
+    pub template: Option<PathBuf>,
+    #[serde(default)]
+    pub color_interior: bool,
+}
+
+/// A chapter of `count` puzzles of one grid size
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SectionConfig {
+    pub title: String,
+    /// Text printed under the chapter heading; blank lines separate paragraphs
+    pub intro: Option<String>,
+    pub count: usize,
+    pub size: usize,
+}
+
+impl ConfigFile {
+    /// Read and check a config file. Relative paths in it are resolved
+    /// against the file's directory.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let mut config = Self::parse(&text)
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        let files = [
+            &mut config.generate.allowlist,
+            &mut config.generate.dictionary,
+            &mut config.generate.clues,
+            &mut config.generate.blocklist,
+            &mut config.generate.used_words,
+            &mut config.cover.template,
+        ];
+        for file in files.into_iter().flatten() {
+            *file = base.join(&*file);
+        }
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        config.check()?;
+        Ok(config)
+    }
+
+    fn check(&self) -> Result<()> {
+        if let Some(count) = self.generate.count {
+            check_count(count).context("[generate] count")?;
+        }
+        if let Some(size) = self.generate.size {
+            check_size(size).context("[generate] size")?;
+        }
+        if let Some(ref format) = self.generate.dictionary_format {
+            format.parse::<crossword_core::DictionaryFormat>()
+                .map_err(anyhow::Error::msg)
+                .context("[generate] dictionary_format")?;
+        }
+        for label in self.generate.exclude_labels.iter().flatten() {
+            if crossword_core::canonical_label(label).is_none() {
+                anyhow::bail!("[generate] exclude_labels: unknown usage label {}. Known labels: {}",
+                              label, crossword_core::known_labels().join(", "));
+            }
+        }
+        if let Some(ref difficulty) = self.generate.clue_difficulty {
+            difficulty.parse::<crossword_core::Difficulty>()
+                .map_err(anyhow::Error::msg)
+                .context("[generate] clue_difficulty")?;
+        }
+        if self.generate.jobs == Some(0) {
+            anyhow::bail!("[generate] jobs must be at least 1");
+        }
+        if self.generate.max_attempts == Some(0) {
+            anyhow::bail!("[generate] max_attempts must be at least 1");
+        }
+        if self.generate.max_repeats == Some(0) {
+            anyhow::bail!("[generate] max_repeats must be at least 1");
+        }
+
+        if !self.sections.is_empty() && (self.generate.count.is_some() || self.generate.size.is_some()) {
+            anyhow::bail!("[generate] count and size cannot be combined with [[section]] tables; give each section its own count and size");
+        }
+        for (idx, section) in self.sections.iter().enumerate() {
+            let label = format!("[[section]] {} ({:?})", idx + 1, section.title);
+            if section.title.trim().is_empty() {
+                anyhow::bail!("[[section]] {}: title is empty", idx + 1);
+            }
+            check_count(section.count).with_context(|| format!("{}: count", label))?;
+            check_size(section.size).with_context(|| format!("{}: size", label))?;
+        }
+        Ok(())
+    }
+}
+
+fn check_count(count: usize) -> Result<()> {
+    if count == 0 {
+        anyhow::bail!("must be at least 1");
+    }
+    Ok(())
+}
+
+fn check_size(size: usize) -> Result<()> {
+    if size < MIN_GRID_SIZE {
+        anyhow::bail!("{} is too small, grids must be at least {}x{}", size, MIN_GRID_SIZE, MIN_GRID_SIZE);
+    }
+    Ok(())
+}
+
+fn kdp_format<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<KdpFormat>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    KdpFormat::from_string(&value).map(Some).map_err(serde::de::Error::custom)
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    crate::batch::parse_duration(&value).map(Some).map_err(serde::de::Error::custom)
+}
+
+fn trim_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<TrimSize>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    TrimSize::from_string(&value).map(Some).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = ConfigFile::parse(r#"
+            title = "Ultimate Crosswords"
+            kdp_format = "ebook"
+            trim_size = "6x9"
+
+            [generate]
+            seed = 7
+            time_limit = "2h"
+            exclude_labels = ["archaic", "derog."]
+
+            [[section]]
+            title = "Warm-up"
+            count = 20
+            size = 10
+
+            [[section]]
+            title = "Expert"
+            intro = "No mercy."
+            count = 5
+            size = 16
+        "#).unwrap();
+        assert_eq!(config.title.as_deref(), Some("Ultimate Crosswords"));
+        assert!(matches!(config.kdp_format, Some(KdpFormat::Ebook)));
+        assert_eq!(config.trim_size.unwrap().width, 6.0);
+        assert_eq!(config.generate.seed, Some(7));
+        assert_eq!(config.generate.time_limit, Some(Duration::from_secs(7200)));
+        assert_eq!(config.generate.exclude_labels.as_deref(), Some(&["archaic".to_string(), "derog.".to_string()][..]));
+        assert_eq!(config.sections.len(), 2);
+        assert_eq!(config.sections[1], SectionConfig {
+            title: "Expert".to_string(),
+            intro: Some("No mercy.".to_string()),
+            count: 5,
+            size: 16,
+        });
+
+        let err = ConfigFile::parse("trim_size = \"6x10\"").unwrap_err();
+        assert!(err.to_string().contains("Invalid trim size: 6x10"));
+        assert!(err.to_string().contains("line 1"));
+
+        let err = ConfigFile::parse("titel = \"Typo\"").unwrap_err();
+        assert!(err.to_string().contains("unknown field `titel`"));
+
+        let err = ConfigFile::parse("[generate]\nexclude_labels = [\"rude\"]").unwrap_err();
+        assert!(err.to_string().starts_with("[generate] exclude_labels: unknown usage label rude"));
+
+        let err = ConfigFile::parse("[[section]]\ntitle = \"Tiny\"\ncount = 1\nsize = 2").unwrap_err();
+        assert_eq!(format!("{:#}", err), "[[section]] 1 (\"Tiny\"): size: 2 is too small, grids must be at least 3x3");
+    }
+}
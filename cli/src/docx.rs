@@ -0,0 +1,394 @@
+use crossword_core::{ClueRecord, CrosswordBook, CrosswordPuzzle};
+use crate::export::escape_xml;
+use anyhow::{Result, Context};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const TWIPS_PER_INCH: f32 = 1440.0;
+const GRID_CELL_TWIPS: u32 = 400;
+const CLUE_HEADER: [&str; 4] = ["No.", "Direction", "Answer", "Clue"];
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+<Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+<Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
+</Types>
+"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
+</Relationships>
+"#;
+
+const DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+</Relationships>
+"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:docDefaults>
+<w:rPrDefault><w:rPr><w:rFonts w:ascii="Times New Roman" w:hAnsi="Times New Roman" w:cs="Times New Roman"/><w:sz w:val="22"/></w:rPr></w:rPrDefault>
+<w:pPrDefault><w:pPr><w:spacing w:after="120"/></w:pPr></w:pPrDefault>
+</w:docDefaults>
+<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+<w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:pPr><w:jc w:val="center"/><w:spacing w:after="240"/></w:pPr><w:rPr><w:b/><w:sz w:val="48"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="32"/></w:rPr></w:style>
+<w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:tblPr><w:tblBorders>
+<w:top w:val="single" w:sz="4" w:space="0" w:color="000000"/><w:left w:val="single" w:sz="4" w:space="0" w:color="000000"/>
+<w:bottom w:val="single" w:sz="4" w:space="0" w:color="000000"/><w:right w:val="single" w:sz="4" w:space="0" w:color="000000"/>
+<w:insideH w:val="single" w:sz="4" w:space="0" w:color="000000"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="000000"/>
+</w:tblBorders></w:tblPr></w:style>
+</w:styles>
+"#;
+
+/// Writes a `CrosswordBook` as a Word document for copy editors: each puzzle
+/// gets its solution grid and a clue table whose Clue column can be edited
+/// (with tracked changes) and merged back with `read_clue_records`.
+pub struct DocxGenerator {}
+
+impl DocxGenerator {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn generate_document(&self, book: &CrosswordBook) -> Result<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let parts = [
+            ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+            ("_rels/.rels", PACKAGE_RELS.to_string()),
+            ("docProps/core.xml", self.generate_core_properties(book)),
+            ("word/_rels/document.xml.rels", DOCUMENT_RELS.to_string()),
+            ("word/styles.xml", STYLES.to_string()),
+            ("word/document.xml", self.generate_body(book)),
+        ];
+        for (name, content) in parts {
+            zip.start_file(name, options)
+                .with_context(|| format!("Failed to add {} to DOCX", name))?;
+            zip.write_all(content.as_bytes())?;
+        }
+
+        let cursor = zip.finish().context("Failed to finish DOCX archive")?;
+        Ok(cursor.into_inner())
+    }
+
+    fn generate_core_properties(&self, book: &CrosswordBook) -> String {
+        let config = book.config();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+        xml.push_str("<cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+        xml.push_str(&format!("<dc:title>{}</dc:title>\n", escape_xml(&config.title)));
+        if let Some(ref author) = config.author {
+            xml.push_str(&format!("<dc:creator>{}</dc:creator>\n", escape_xml(author)));
+        }
+        xml.push_str("</cp:coreProperties>\n");
+        xml
+    }
+
+    fn generate_body(&self, book: &CrosswordBook) -> String {
+        let config = book.config();
+        let records = book.clue_records();
+
+        let mut body = String::new();
+        body.push_str(&paragraph(Some("Title"), &config.title, false));
+        body.push_str(&paragraph(
+            None,
+            "Edit clues in the Clue column only. Answers, numbers and directions are checked when the document is merged back into the book.",
+            false,
+        ));
+
+        for chapter in book.chapters() {
+            // A section heading starts the page its first puzzle goes on
+            if let Some(section) = chapter.section {
+                body.push_str(&paragraph(Some("Heading1"), &section.title, chapter.first_number > 1));
+                if let Some(summary) = chapter.summary() {
+                    body.push_str(&paragraph(None, &summary, false));
+                }
+            }
+            for (offset, puzzle) in chapter.puzzles.iter().enumerate() {
+                let number = chapter.first_number + offset;
+                let page_break = number > 1 && (offset > 0 || chapter.section.is_none());
+                body.push_str(&paragraph(Some("Heading1"), &format!("Puzzle {}", number), page_break));
+                body.push_str(&grid_table(puzzle));
+                body.push_str(&paragraph(None, "", false));
+                let rows: Vec<&ClueRecord> = records.iter().filter(|r| r.puzzle == number).collect();
+                body.push_str(&clue_table(&rows));
+            }
+        }
+
+        // Page size follows the book's trim size, with 1" margins
+        let width = (config.trim_size.width * TWIPS_PER_INCH).round() as u32;
+        let height = (config.trim_size.height * TWIPS_PER_INCH).round() as u32;
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\n<w:body>\n{}\
+             <w:sectPr><w:pgSz w:w=\"{}\" w:h=\"{}\"/><w:pgMar w:top=\"1440\" w:right=\"1440\" w:bottom=\"1440\" w:left=\"1440\" w:header=\"720\" w:footer=\"720\" w:gutter=\"0\"/></w:sectPr>\n\
+             </w:body>\n</w:document>\n",
+            body, width, height
+        )
+    }
+}
+
+impl Default for DocxGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn run(text: &str, props: &str) -> String {
+    let props = if props.is_empty() { String::new() } else { format!("<w:rPr>{}</w:rPr>", props) };
+    format!("<w:r>{}<w:t xml:space=\"preserve\">{}</w:t></w:r>", props, escape_xml(text))
+}
+
+fn paragraph(style: Option<&str>, text: &str, page_break: bool) -> String {
+    let mut props = String::new();
+    if let Some(style) = style {
+        props.push_str(&format!("<w:pStyle w:val=\"{}\"/>", style));
+    }
+    if page_break {
+        props.push_str("<w:pageBreakBefore/>");
+    }
+    let props = if props.is_empty() { String::new() } else { format!("<w:pPr>{}</w:pPr>", props) };
+    let content = if text.is_empty() { String::new() } else { run(text, "") };
+    format!("<w:p>{}{}</w:p>\n", props, content)
+}
+
+fn table_cell(width: u32, content: &str, props: &str) -> String {
+    format!(
+        "<w:tc><w:tcPr><w:tcW w:w=\"{}\" w:type=\"dxa\"/>{}</w:tcPr>{}</w:tc>",
+        width, props, content
+    )
+}
+
+/// Solution grid with small clue numbers, blocks shaded black
+fn grid_table(puzzle: &CrosswordPuzzle) -> String {
+    let size = puzzle.grid.len();
+    let numbers = puzzle.cell_numbers();
+
+    let mut xml = String::from("<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:jc w:val=\"center\"/><w:tblLayout w:type=\"fixed\"/>\
+        <w:tblCellMar><w:left w:w=\"20\" w:type=\"dxa\"/><w:right w:w=\"20\" w:type=\"dxa\"/></w:tblCellMar></w:tblPr><w:tblGrid>");
+    for _ in 0..size {
+        xml.push_str(&format!("<w:gridCol w:w=\"{}\"/>", GRID_CELL_TWIPS));
+    }
+    xml.push_str("</w:tblGrid>\n");
+
+    for (row, cells) in puzzle.grid.iter().enumerate() {
+        xml.push_str(&format!("<w:tr><w:trPr><w:trHeight w:val=\"{}\" w:hRule=\"exact\"/></w:trPr>", GRID_CELL_TWIPS));
+        for (col, letter) in cells.iter().enumerate() {
+            match letter {
+                None => xml.push_str(&table_cell(
+                    GRID_CELL_TWIPS,
+                    "<w:p/>",
+                    "<w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"000000\"/>",
+                )),
+                Some(letter) => {
+                    let mut content = String::from("<w:p><w:pPr><w:spacing w:after=\"0\"/><w:jc w:val=\"center\"/></w:pPr>");
+                    if let Some(number) = numbers[row][col] {
+                        content.push_str(&run(&number.to_string(), "<w:vertAlign w:val=\"superscript\"/><w:sz w:val=\"14\"/>"));
+                    }
+                    content.push_str(&run(&letter.to_string(), "<w:sz w:val=\"20\"/>"));
+                    content.push_str("</w:p>");
+                    xml.push_str(&table_cell(GRID_CELL_TWIPS, &content, "<w:vAlign w:val=\"center\"/>"));
+                }
+            }
+        }
+        xml.push_str("</w:tr>\n");
+    }
+    xml.push_str("</w:tbl>\n");
+    xml
+}
+
+fn clue_table(records: &[&ClueRecord]) -> String {
+    let widths = [700, 1100, 1800, 4400];
+
+    let mut xml = String::from("<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr><w:tblGrid>");
+    for width in widths {
+        xml.push_str(&format!("<w:gridCol w:w=\"{}\"/>", width));
+    }
+    xml.push_str("</w:tblGrid>\n");
+
+    let mut push_row = |cells: [String; 4], header: bool| {
+        xml.push_str("<w:tr>");
+        if header {
+            xml.push_str("<w:trPr><w:tblHeader/></w:trPr>");
+        }
+        for (text, width) in cells.iter().zip(widths) {
+            let content = format!("<w:p><w:pPr><w:spacing w:after=\"0\"/></w:pPr>{}</w:p>", run(text, if header { "<w:b/>" } else { "" }));
+            xml.push_str(&table_cell(width, &content, ""));
+        }
+        xml.push_str("</w:tr>\n");
+    };
+
+    push_row(CLUE_HEADER.map(String::from), true);
+    for record in records {
+        push_row([
+            record.number.to_string(),
+            record.direction.as_str().to_string(),
+            record.answer.clone(),
+            record.clue.clone(),
+        ], false);
+    }
+
+    xml.push_str("</w:tbl>\n");
+    xml
+}
+
+/// Read the clue tables back from an edited DOCX. Tracked insertions are
+/// taken as accepted and tracked deletions are dropped.
+pub fn read_clue_records(data: &[u8]) -> Result<Vec<ClueRecord>> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).context("Not a DOCX (zip) file")?;
+    let mut xml = String::new();
+    archive.by_name("word/document.xml")
+        .context("DOCX has no word/document.xml")?
+        .read_to_string(&mut xml)?;
+
+    let mut records = Vec::new();
+    for (puzzle, table) in read_tables(&xml)? {
+        let Some(header) = table.first() else { continue };
+        if header.len() != CLUE_HEADER.len() || header.iter().zip(CLUE_HEADER).any(|(a, b)| a.trim() != b) {
+            continue; // grid or other table
+        }
+        let puzzle = puzzle.context("Clue table found before any \"Puzzle N\" heading")?;
+
+        for (row_idx, row) in table.iter().enumerate().skip(1) {
+            if row.iter().all(|cell| cell.trim().is_empty()) {
+                continue;
+            }
+            let [number, direction, answer, clue] = row.as_slice() else {
+                anyhow::bail!("Puzzle {} clue table row {}: expected 4 cells, found {}", puzzle, row_idx, row.len());
+            };
+            records.push(ClueRecord {
+                puzzle,
+                number: number.trim().parse()
+                    .with_context(|| format!("Puzzle {} clue table row {}: invalid number '{}'", puzzle, row_idx, number.trim()))?,
+                direction: direction.parse()
+                    .map_err(anyhow::Error::msg)
+                    .with_context(|| format!("Puzzle {} clue table row {}", puzzle, row_idx))?,
+                answer: answer.trim().to_string(),
+                clue: clue.trim().to_string(),
+            });
+        }
+    }
+
+    if records.is_empty() {
+        anyhow::bail!("No clue tables found in DOCX");
+    }
+    Ok(records)
+}
+
+type Table = Vec<Vec<String>>;
+
+/// Every top-level table's cell texts, with the puzzle number from the
+/// closest preceding "Puzzle N" paragraph
+fn read_tables(xml: &str) -> Result<Vec<(Option<usize>, Table)>> {
+    let mut reader = Reader::from_str(xml);
+    let mut tables = Vec::new();
+    let mut puzzle = None;
+
+    let mut depth = 0;
+    let mut table: Table = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell = String::new();
+    let mut paragraph = String::new();
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event().context("Malformed word/document.xml")? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"tbl" => {
+                    depth += 1;
+                    if depth == 1 {
+                        table.clear();
+                    }
+                }
+                b"tr" if depth == 1 => row.clear(),
+                b"tc" if depth == 1 => cell.clear(),
+                b"p" => paragraph.clear(),
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"tab" | b"br" => paragraph.push(' '),
+                _ => {}
+            },
+            Event::Text(t) if in_text => {
+                paragraph.push_str(&t.unescape().context("Malformed text in word/document.xml")?);
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" if depth == 0 => {
+                    if let Some(number) = paragraph.trim().strip_prefix("Puzzle ").and_then(|n| n.trim().parse().ok()) {
+                        puzzle = Some(number);
+                    }
+                }
+                b"p" if depth == 1 => {
+                    if !cell.is_empty() && !paragraph.is_empty() {
+                        cell.push(' ');
+                    }
+                    cell.push_str(&paragraph);
+                }
+                b"tc" if depth == 1 => row.push(cell.trim().to_string()),
+                b"tr" if depth == 1 => table.push(std::mem::take(&mut row)),
+                b"tbl" => {
+                    if depth == 1 {
+                        tables.push((puzzle, std::mem::take(&mut table)));
+                    }
+                    depth -= 1;
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::BookConfig;
+    use crossword_core::test_util::sample_puzzle;
+
+    #[test]
+    fn test_docx_round_trip() {
+        let mut book = CrosswordBook::new(BookConfig::new("Round & Trip".to_string(), 3));
+        book.add_puzzle(sample_puzzle(3, |w| format!("Old <{}>", w)));
+
+        let bytes = DocxGenerator::new().generate_document(&book).unwrap();
+        let mut records = read_clue_records(&bytes).unwrap();
+        assert_eq!(records, book.clue_records());
+
+        // Simulate an editor's tracked change in the Word XML
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut xml = String::new();
+        archive.by_name("word/document.xml").unwrap().read_to_string(&mut xml).unwrap();
+        let edited = xml.replace(
+            "<w:t xml:space=\"preserve\">Old &lt;CAT&gt;</w:t></w:r>",
+            "<w:t xml:space=\"preserve\">Old &lt;CAT&gt;</w:t></w:r><w:del><w:r><w:delText>x</w:delText></w:r></w:del>\
+             <w:ins><w:r><w:t xml:space=\"preserve\">, purring</w:t></w:r></w:ins>",
+        );
+        let tables = read_tables(&edited).unwrap();
+        assert_eq!(tables[1].1[1][3], "Old <CAT>, purring");
+
+        records[0].clue = "Feline".to_string();
+        assert_eq!(book.merge_clues(&records).unwrap(), 1);
+        assert_eq!(book.puzzles()[0].across_clues[0].clue, "Feline");
+
+        records[1].answer = "COB".to_string();
+        let err = book.merge_clues(&records).unwrap_err().to_string();
+        assert!(err.contains("Puzzle 1 1 Down: answer COB does not match CAB"));
+    }
+}
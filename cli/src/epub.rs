@@ -0,0 +1,421 @@
+use crossword_core::{drop_cap, render_puzzle_svg, render_solution_svg, BookConfig, Clue, CrosswordBook, CrosswordPuzzle, Section, SvgOptions, INTRODUCTION, INTRODUCTION_TITLES};
+use crate::export::{copyright_notice, escape_xml, puzzle_file_name};
+use anyhow::{Result, Context};
+use std::io::{Cursor, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const STYLE: &str = r#"body { font-family: serif; margin: 0 0.5em; }
+h1 { text-align: center; margin: 1em 0 0.5em; }
+h2 { margin: 1em 0 0.3em; font-size: 1.2em; }
+h2.section { font-size: 1.5em; text-align: center; margin-top: 1.5em; }
+.summary { font-size: 1.1em; font-style: italic; }
+.title-page { text-align: center; margin-top: 30%; }
+.title-page .subtitle { font-size: 1.3em; font-style: italic; }
+.title-page .author { font-size: 1.2em; margin-top: 2em; }
+.copyright { text-align: center; margin-top: 30%; font-size: 0.9em; }
+.dropcap { float: left; font-size: 3.2em; line-height: 0.9; margin-right: 0.08em; }
+.grid { text-align: center; margin: 1em 0; }
+.grid svg { width: 100%; max-width: 30em; height: auto; }
+ol.clues { padding-left: 2.5em; margin: 0; }
+ol.clues li { margin-bottom: 0.2em; }
+.answer-link { text-align: right; font-size: 0.9em; }
+"#;
+
+/// Writes a `CrosswordBook` as a reflowable EPUB 3 with inline SVG grids,
+/// for ebook stores where a fixed-page PDF reads poorly.
+pub struct EpubGenerator {}
+
+impl EpubGenerator {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn generate_document(&self, book: &CrosswordBook) -> Result<Vec<u8>> {
+        let config = book.config();
+        let count = book.puzzle_count();
+
+        let mut chapters = vec![
+            Chapter::new("title.xhtml", "Title Page", self.generate_title_page(config)),
+            Chapter::new("copyright.xhtml", "Copyright", self.generate_copyright_page(config)),
+            Chapter::new("intro.xhtml", "Introduction", self.generate_introduction()),
+        ];
+        for book_chapter in book.chapters() {
+            if let Some(section) = book_chapter.section {
+                chapters.push(Chapter::new(
+                    &format!("section-{:03}.xhtml", book_chapter.first_number),
+                    &section.title,
+                    self.generate_section_opener(section, &book_chapter),
+                ));
+            }
+            for (offset, puzzle) in book_chapter.puzzles.iter().enumerate() {
+                let number = book_chapter.first_number + offset;
+                let mut chapter = Chapter::new(
+                    &puzzle_file_name(number, "xhtml"),
+                    &format!("Puzzle {}", number),
+                    self.generate_puzzle_chapter(puzzle, number),
+                );
+                chapter.svg = true;
+                chapters.push(chapter);
+            }
+        }
+        let mut answers = Chapter::new("answers.xhtml", "Answer Key", self.generate_answer_key(book));
+        answers.svg = count > 0;
+        chapters.push(answers);
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+        // The mimetype entry must come first and be stored uncompressed
+        zip.start_file("mimetype", SimpleFileOptions::default().compression_method(CompressionMethod::Stored))?;
+        zip.write_all(b"application/epub+zip")?;
+
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut add = |name: &str, content: &str| -> Result<()> {
+            zip.start_file(name, deflated)
+                .with_context(|| format!("Failed to add {} to EPUB", name))?;
+            zip.write_all(content.as_bytes())?;
+            Ok(())
+        };
+
+        add("META-INF/container.xml", CONTAINER)?;
+        add("OEBPS/content.opf", &self.generate_package(config, &chapters))?;
+        add("OEBPS/nav.xhtml", &self.generate_nav(&chapters))?;
+        add("OEBPS/style.css", STYLE)?;
+        for chapter in &chapters {
+            add(&format!("OEBPS/{}", chapter.file), &xhtml_page(&chapter.title, &chapter.body))?;
+        }
+
+        let cursor = zip.finish().context("Failed to finish EPUB archive")?;
+        Ok(cursor.into_inner())
+    }
+
+    fn generate_title_page(&self, config: &BookConfig) -> String {
+        let mut body = String::from("<section class=\"title-page\" epub:type=\"titlepage\">\n");
+        body.push_str(&format!("<h1>{}</h1>\n", escape_xml(&config.title)));
+        if let Some(ref subtitle) = config.subtitle {
+            body.push_str(&format!("<p class=\"subtitle\">{}</p>\n", escape_xml(subtitle)));
+        }
+        if let Some(ref author) = config.author {
+            body.push_str(&format!("<p class=\"author\">{}</p>\n", escape_xml(author)));
+        }
+        if let Some(ref publisher) = config.publisher {
+            body.push_str(&format!("<p>{}</p>\n", escape_xml(publisher)));
+        }
+        body.push_str("</section>\n");
+        body
+    }
+
+    fn generate_copyright_page(&self, config: &BookConfig) -> String {
+        let mut body = String::from("<section class=\"copyright\" epub:type=\"copyright-page\">\n");
+        if let Some(ref year) = config.copyright_year {
+            let line = format!("Copyright © {} {}", year, config.author.as_deref().unwrap_or(""));
+            body.push_str(&format!("<p>{}</p>\n", escape_xml(line.trim())));
+        }
+        body.push_str("<p>All rights reserved.</p>\n");
+        body.push_str("<p>No part of this publication may be reproduced, distributed, or transmitted in any form or by any means, without the prior written permission of the publisher.</p>\n");
+        if let Some(ref edition) = config.edition {
+            body.push_str(&format!("<p>{}</p>\n", escape_xml(edition)));
+        }
+        if let Some(ref isbn) = config.isbn {
+            body.push_str(&format!("<p>ISBN: {}</p>\n", escape_xml(isbn)));
+        }
+        if let Some(ref publisher) = config.publisher {
+            body.push_str(&format!("<p>Published by {}</p>\n", escape_xml(publisher)));
+        }
+        body.push_str("</section>\n");
+        body
+    }
+
+    fn generate_introduction(&self) -> String {
+        let mut body = String::from("<section epub:type=\"introduction\">\n<h1>Introduction</h1>\n");
+        let italics = |text: &str| {
+            INTRODUCTION_TITLES.iter().fold(escape_xml(text), |text, title| text.replace(title, &format!("<i>{}</i>", title)))
+        };
+        let (initial, opening) = drop_cap(INTRODUCTION[0]);
+        body.push_str(&format!("<p><span class=\"dropcap\">{}</span>{}</p>\n", escape_xml(initial), italics(opening)));
+        for paragraph in &INTRODUCTION[1..] {
+            body.push_str(&format!("<p>{}</p>\n", italics(paragraph)));
+        }
+        body.push_str("</section>\n");
+        body
+    }
+
+    fn generate_section_opener(&self, section: &Section, chapter: &crossword_core::Chapter) -> String {
+        let mut body = format!("<section epub:type=\"part\">\n<h1>{}</h1>\n", escape_xml(&section.title));
+        if let Some(summary) = chapter.summary() {
+            body.push_str(&format!("<p class=\"summary\">{}</p>\n", escape_xml(&summary)));
+        }
+        for paragraph in section.intro_paragraphs() {
+            body.push_str(&format!("<p>{}</p>\n", escape_xml(paragraph)));
+        }
+        body.push_str("</section>\n");
+        body
+    }
+
+    fn generate_puzzle_chapter(&self, puzzle: &CrosswordPuzzle, number: usize) -> String {
+        let mut body = format!("<section epub:type=\"chapter\">\n<h1>Puzzle {}</h1>\n", number);
+        body.push_str("<div class=\"grid\">\n");
+        body.push_str(&render_puzzle_svg(puzzle, &SvgOptions::default()));
+        body.push_str("</div>\n");
+        body.push_str(&clue_list("Across", &puzzle.across_clues));
+        body.push_str(&clue_list("Down", &puzzle.down_clues));
+        body.push_str(&format!(
+            "<p class=\"answer-link\"><a href=\"answers.xhtml#solution-{}\">Solution</a></p>\n",
+            number
+        ));
+        body.push_str("</section>\n");
+        body
+    }
+
+    fn generate_answer_key(&self, book: &CrosswordBook) -> String {
+        let mut body = String::from("<section epub:type=\"appendix\">\n<h1>Answer Key</h1>\n");
+        for chapter in book.chapters() {
+            if let Some(section) = chapter.section {
+                body.push_str(&format!("<h2 class=\"section\">{}</h2>\n", escape_xml(&section.title)));
+            }
+            for (offset, puzzle) in chapter.puzzles.iter().enumerate() {
+                let number = chapter.first_number + offset;
+                body.push_str(&format!(
+                    "<h2 id=\"solution-{}\"><a href=\"{}\">Puzzle {}</a></h2>\n",
+                    number,
+                    puzzle_file_name(number, "xhtml"),
+                    number
+                ));
+                body.push_str("<div class=\"grid\">\n");
+                body.push_str(&render_solution_svg(puzzle, &SvgOptions::default()));
+                body.push_str("</div>\n");
+            }
+        }
+        body.push_str("</section>\n");
+        body
+    }
+
+    fn generate_nav(&self, chapters: &[Chapter]) -> String {
+        let mut body = String::from("<nav epub:type=\"toc\" id=\"toc\">\n<h1>Contents</h1>\n<ol>\n");
+        for chapter in chapters {
+            body.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                chapter.file,
+                escape_xml(&chapter.title)
+            ));
+        }
+        body.push_str("</ol>\n</nav>\n");
+
+        body.push_str("<nav epub:type=\"landmarks\" hidden=\"hidden\">\n<ol>\n");
+        body.push_str("<li><a epub:type=\"toc\" href=\"nav.xhtml\">Contents</a></li>\n");
+        body.push_str("<li><a epub:type=\"bodymatter\" href=\"intro.xhtml\">Introduction</a></li>\n");
+        body.push_str("</ol>\n</nav>\n");
+
+        xhtml_page("Contents", &body)
+    }
+
+    fn generate_package(&self, config: &BookConfig, chapters: &[Chapter]) -> String {
+        let identifier = match config.isbn {
+            Some(ref isbn) => format!("urn:isbn:{}", isbn.replace(['-', ' '], "")),
+            None => format!("urn:uuid:{}", book_uuid(config)),
+        };
+
+        let mut opf = String::new();
+        opf.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        opf.push_str("<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\" xml:lang=\"en\">\n");
+        opf.push_str("<metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+        opf.push_str(&format!("<dc:identifier id=\"book-id\">{}</dc:identifier>\n", escape_xml(&identifier)));
+        opf.push_str(&format!("<dc:title>{}</dc:title>\n", escape_xml(&config.title)));
+        opf.push_str("<dc:language>en</dc:language>\n");
+        if let Some(ref author) = config.author {
+            opf.push_str(&format!("<dc:creator>{}</dc:creator>\n", escape_xml(author)));
+        }
+        if let Some(ref publisher) = config.publisher {
+            opf.push_str(&format!("<dc:publisher>{}</dc:publisher>\n", escape_xml(publisher)));
+        }
+        if let Some(ref description) = config.description {
+            opf.push_str(&format!("<dc:description>{}</dc:description>\n", escape_xml(description)));
+        }
+        if let Some(ref year) = config.copyright_year {
+            opf.push_str(&format!("<dc:date>{}</dc:date>\n", escape_xml(year)));
+        }
+        if let Some(notice) = copyright_notice(config) {
+            opf.push_str(&format!("<dc:rights>{}</dc:rights>\n", escape_xml(&notice)));
+        }
+        opf.push_str(&format!("<meta property=\"dcterms:modified\">{}</meta>\n", utc_timestamp()));
+        opf.push_str("</metadata>\n");
+
+        opf.push_str("<manifest>\n");
+        opf.push_str("<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n");
+        opf.push_str("<item id=\"css\" href=\"style.css\" media-type=\"text/css\"/>\n");
+        for chapter in chapters {
+            opf.push_str(&format!(
+                "<item id=\"{}\" href=\"{}\" media-type=\"application/xhtml+xml\"{}/>\n",
+                chapter.id(),
+                chapter.file,
+                if chapter.svg { " properties=\"svg\"" } else { "" }
+            ));
+        }
+        opf.push_str("</manifest>\n");
+
+        opf.push_str("<spine>\n");
+        for (idx, chapter) in chapters.iter().enumerate() {
+            opf.push_str(&format!("<itemref idref=\"{}\"/>\n", chapter.id()));
+            if idx == 1 {
+                // Contents after the copyright page, as in print
+                opf.push_str("<itemref idref=\"nav\"/>\n");
+            }
+        }
+        opf.push_str("</spine>\n");
+        opf.push_str("</package>\n");
+        opf
+    }
+}
+
+impl Default for EpubGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>
+"#;
+
+struct Chapter {
+    file: String,
+    title: String,
+    body: String,
+    svg: bool,
+}
+
+impl Chapter {
+    fn new(file: &str, title: &str, body: String) -> Self {
+        Self {
+            file: file.to_string(),
+            title: title.to_string(),
+            body,
+            svg: false,
+        }
+    }
+
+    /// Manifest id from the file name ("puzzle-001.xhtml" -> "puzzle-001")
+    fn id(&self) -> &str {
+        self.file.trim_end_matches(".xhtml")
+    }
+}
+
+fn xhtml_page(title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" xml:lang=\"en\" lang=\"en\">\n\
+         <head>\n<meta charset=\"UTF-8\"/>\n<title>{}</title>\n<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n</head>\n\
+         <body>\n{}</body>\n</html>\n",
+        escape_xml(title),
+        body
+    )
+}
+
+fn clue_list(heading: &str, clues: &[Clue]) -> String {
+    let mut html = format!("<h2>{}</h2>\n<ol class=\"clues\">\n", heading);
+    for clue in clues {
+        html.push_str(&format!(
+            "<li value=\"{}\">{} ({})</li>\n",
+            clue.number,
+            escape_xml(&clue.clue),
+            clue.word.chars().count()
+        ));
+    }
+    html.push_str("</ol>\n");
+    html
+}
+
+/// Stable UUID-shaped identifier so re-exports of the same book keep their identity
+fn book_uuid(config: &BookConfig) -> String {
+    let key = format!("{}\u{0}{}", config.title, config.author.as_deref().unwrap_or(""));
+    let fnv = |seed: u64| {
+        key.bytes().fold(seed, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+    };
+    let hex = format!("{:016x}{:016x}", fnv(0xcbf29ce484222325), fnv(0x84222325cbf29ce4));
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+/// Current time as "YYYY-MM-DDThh:mm:ssZ"
+fn utc_timestamp() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::sample_puzzle;
+    use std::io::Read;
+
+    #[test]
+    fn test_epub_structure() {
+        let mut config = BookConfig::new("Test & Book".to_string(), 3);
+        config.author = Some("Ann Author".to_string());
+        let mut book = CrosswordBook::new(config);
+        book.add_puzzle(sample_puzzle(3, |w| format!("Clue <{}>", w)));
+
+        let bytes = EpubGenerator::new().generate_document(&book).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let first = archive.by_index(0).unwrap();
+        assert_eq!(first.name(), "mimetype");
+        assert_eq!(first.compression(), CompressionMethod::Stored);
+        drop(first);
+
+        let names: Vec<String> = archive.file_names().map(|n| n.to_string()).collect();
+        for name in &names {
+            if !name.ends_with(".xhtml") && !name.ends_with(".opf") && !name.ends_with(".xml") {
+                continue;
+            }
+            let mut content = String::new();
+            archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+
+            // Every XML document must be well-formed
+            let mut reader = quick_xml::Reader::from_str(&content);
+            loop {
+                match reader.read_event() {
+                    Ok(quick_xml::events::Event::Eof) => break,
+                    Ok(_) => {}
+                    Err(e) => panic!("{} is not well-formed: {}", name, e),
+                }
+            }
+
+            if name == "OEBPS/content.opf" {
+                assert!(content.contains("<dc:title>Test &amp; Book</dc:title>"));
+                assert!(content.contains("<itemref idref=\"puzzle-001\"/>"));
+                assert!(content.contains("href=\"puzzle-001.xhtml\" media-type=\"application/xhtml+xml\" properties=\"svg\""));
+            }
+            if name == "OEBPS/puzzle-001.xhtml" {
+                assert!(content.contains("<li value=\"1\">Clue &lt;CAT&gt; (3)</li>"));
+                assert!(content.contains("answers.xhtml#solution-1"));
+            }
+            if name == "OEBPS/answers.xhtml" {
+                assert!(content.contains("<h2 id=\"solution-1\"><a href=\"puzzle-001.xhtml\">"));
+            }
+        }
+        assert!(names.contains(&"OEBPS/nav.xhtml".to_string()));
+        assert!(names.contains(&"META-INF/container.xml".to_string()));
+    }
+}
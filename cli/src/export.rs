@@ -0,0 +1,191 @@
+use crossword_core::{BookConfig, CrosswordBook, CrosswordPuzzle, render_puzzle_svg, render_solution_svg, render_puzzle_text, render_solution_text, IpuzDocument, IpuzMetadata, SvgOptions, TextOptions, TextStyle};
+use crate::html::HtmlGenerator;
+use crate::jpz::JpzGenerator;
+use crate::raster::{render_png, PngOptions};
+use anyhow::{Result, Context};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Write one `.ipuz` file per puzzle into `dir`
+pub fn export_ipuz(book: &CrosswordBook, dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let mut written = Vec::new();
+    for (idx, puzzle) in book.puzzles().iter().enumerate() {
+        let metadata = ipuz_metadata(book.config(), idx + 1);
+        let json = IpuzDocument::from_puzzle(puzzle, metadata)
+            .to_json()
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let path = dir.join(puzzle_file_name(idx + 1, "ipuz"));
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Write one `.jpz` file per puzzle into `dir`
+pub fn export_jpz(book: &CrosswordBook, dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let jpz_gen = JpzGenerator::new();
+    let mut written = Vec::new();
+    for (idx, puzzle) in book.puzzles().iter().enumerate() {
+        let xml = jpz_gen.generate_puzzle(puzzle, book.config(), idx + 1);
+
+        let path = dir.join(puzzle_file_name(idx + 1, "jpz"));
+        fs::write(&path, xml)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Write one interactive `.html` page per puzzle into `dir`
+pub fn export_html(book: &CrosswordBook, dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let html_gen = HtmlGenerator::new();
+    let mut written = Vec::new();
+    for (idx, puzzle) in book.puzzles().iter().enumerate() {
+        let html = html_gen.generate_puzzle_page(puzzle, book.config(), idx + 1);
+
+        let path = dir.join(puzzle_file_name(idx + 1, "html"));
+        fs::write(&path, html)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Write a blank and a solution `.svg` per puzzle into `dir`
+pub fn export_svg(book: &CrosswordBook, dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let options = SvgOptions::default();
+    let mut written = Vec::new();
+    for (idx, puzzle) in book.puzzles().iter().enumerate() {
+        let path = dir.join(puzzle_file_name(idx + 1, "svg"));
+        fs::write(&path, render_puzzle_svg(puzzle, &options))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+
+        let path = dir.join(puzzle_file_name(idx + 1, "solution.svg"));
+        fs::write(&path, render_solution_svg(puzzle, &options))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Write `puzzle-NNN.txt` (grid and clues) and `puzzle-NNN.solution.txt` for each puzzle
+pub fn export_txt(book: &CrosswordBook, dir: &Path, style: TextStyle) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let options = TextOptions { style, ..Default::default() };
+    let mut written = Vec::new();
+    for (idx, puzzle) in book.puzzles().iter().enumerate() {
+        let heading = format!("{} - Puzzle {}\n\n", book.config().title, idx + 1);
+
+        let path = dir.join(puzzle_file_name(idx + 1, "txt"));
+        fs::write(&path, format!("{}{}", heading, render_puzzle_text(puzzle, &options)))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+
+        let path = dir.join(puzzle_file_name(idx + 1, "solution.txt"));
+        fs::write(&path, format!("{}{}", heading, render_solution_text(puzzle, &options)))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Write `puzzle-NNN.png` and `puzzle-NNN.solution.png` for each puzzle
+pub fn export_png(book: &CrosswordBook, dir: &Path, options: &PngOptions) -> Result<Vec<PathBuf>> {
+    options.check()?;
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let mut written = Vec::new();
+    for (idx, puzzle) in book.puzzles().iter().enumerate() {
+        for (solution, ext) in [(false, "png"), (true, "solution.png")] {
+            let path = dir.join(puzzle_file_name(idx + 1, ext));
+            fs::write(&path, render_png(puzzle, solution, options)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+        }
+    }
+
+    Ok(written)
+}
+
+/// One puzzle in one export format, in memory: the file's bytes and its
+/// MIME type. `solution` picks the filled grid for svg, png and txt.
+pub fn export_puzzle(
+    puzzle: &CrosswordPuzzle,
+    config: &BookConfig,
+    number: usize,
+    format: &str,
+    solution: bool,
+) -> Result<(Vec<u8>, &'static str)> {
+    let text_options = TextOptions { style: TextStyle::Unicode, ..Default::default() };
+    let svg_options = SvgOptions::default();
+    let file = match format.to_lowercase().as_str() {
+        "ipuz" => {
+            let json = IpuzDocument::from_puzzle(puzzle, ipuz_metadata(config, number))
+                .to_json()
+                .map_err(|e| anyhow::anyhow!(e))?;
+            (json.into_bytes(), "application/json")
+        }
+        "jpz" => (JpzGenerator::new().generate_puzzle(puzzle, config, number).into_bytes(), "application/xml"),
+        "html" => (HtmlGenerator::new().generate_puzzle_page(puzzle, config, number).into_bytes(), "text/html; charset=utf-8"),
+        "svg" if solution => (render_solution_svg(puzzle, &svg_options).into_bytes(), "image/svg+xml"),
+        "svg" => (render_puzzle_svg(puzzle, &svg_options).into_bytes(), "image/svg+xml"),
+        "png" => (render_png(puzzle, solution, &PngOptions::default())?, "image/png"),
+        "txt" | "text" if solution => (render_solution_text(puzzle, &text_options).into_bytes(), "text/plain; charset=utf-8"),
+        "txt" | "text" => (render_puzzle_text(puzzle, &text_options).into_bytes(), "text/plain; charset=utf-8"),
+        other => anyhow::bail!("Invalid export format: {}. Use ipuz, jpz, html, svg, png or txt", other),
+    };
+    Ok(file)
+}
+
+fn ipuz_metadata(config: &BookConfig, number: usize) -> IpuzMetadata {
+    IpuzMetadata {
+        title: Some(format!("{} - Puzzle {}", config.title, number)),
+        author: config.author.clone(),
+        publisher: config.publisher.clone(),
+        copyright: copyright_notice(config),
+        ..Default::default()
+    }
+}
+
+/// "© 2024 Author" style notice, if a copyright year is configured
+pub fn copyright_notice(config: &BookConfig) -> Option<String> {
+    config.copyright_year.as_ref().map(|year| match config.author {
+        Some(ref author) => format!("© {} {}", year, author),
+        None => format!("© {}", year),
+    })
+}
+
+pub fn puzzle_file_name(number: usize, extension: &str) -> String {
+    format!("puzzle-{:03}.{}", number, extension)
+}
+
+pub fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,472 @@
+use crossword_core::{BookConfig, Chapter, Clue, CrosswordBook, CrosswordPuzzle, Section};
+use crate::export::{copyright_notice, escape_xml};
+use serde_json::json;
+
+/// Writes self-contained, solvable HTML pages (no server, no external assets)
+pub struct HtmlGenerator {}
+
+impl HtmlGenerator {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// A page holding a single puzzle
+    pub fn generate_puzzle_page(&self, puzzle: &CrosswordPuzzle, config: &BookConfig, number: usize) -> String {
+        let title = format!("{} - Puzzle {}", config.title, number);
+        let body = self.generate_puzzle_section(puzzle, number);
+        self.wrap_page(&title, config, &body)
+    }
+
+    /// A page holding every puzzle in the book, with a table of contents
+    pub fn generate_book_page(&self, book: &CrosswordBook) -> String {
+        let config = book.config();
+        let mut body = String::new();
+
+        body.push_str("<header class=\"book-title\">\n");
+        body.push_str(&format!("<h1>{}</h1>\n", escape_xml(&config.title)));
+        if let Some(ref subtitle) = config.subtitle {
+            body.push_str(&format!("<p class=\"subtitle\">{}</p>\n", escape_xml(subtitle)));
+        }
+        if let Some(ref author) = config.author {
+            body.push_str(&format!("<p class=\"author\">{}</p>\n", escape_xml(author)));
+        }
+        let chapters = book.chapters();
+        body.push_str("<nav class=\"toc\">\n");
+        for chapter in &chapters {
+            if let Some(section) = chapter.section {
+                body.push_str(&format!(
+                    "<h2><a href=\"#section-{}\">{}</a></h2>\n",
+                    chapter.first_number,
+                    escape_xml(&section.title)
+                ));
+            }
+            body.push_str(&format!("<ol start=\"{}\">\n", chapter.first_number));
+            for number in chapter.first_number..=chapter.last_number() {
+                body.push_str(&format!(
+                    "<li><a href=\"#puzzle-{0}\">Puzzle {0}</a></li>\n",
+                    number
+                ));
+            }
+            body.push_str("</ol>\n");
+        }
+        body.push_str("</nav>\n");
+        body.push_str("</header>\n");
+
+        for chapter in &chapters {
+            if let Some(section) = chapter.section {
+                body.push_str(&self.generate_section_heading(section, chapter));
+            }
+            for (offset, puzzle) in chapter.puzzles.iter().enumerate() {
+                body.push_str(&self.generate_puzzle_section(puzzle, chapter.first_number + offset));
+            }
+        }
+
+        self.wrap_page(&config.title, config, &body)
+    }
+
+    fn wrap_page(&self, title: &str, config: &BookConfig, body: &str) -> String {
+        let mut html = String::new();
+
+        html.push_str("<!DOCTYPE html>\n");
+        html.push_str("<html lang=\"en\">\n<head>\n");
+        html.push_str("<meta charset=\"utf-8\">\n");
+        html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+        html.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+        html.push_str("<style>\n");
+        html.push_str(STYLE);
+        html.push_str("</style>\n");
+        html.push_str("</head>\n<body>\n");
+        html.push_str(body);
+
+        if let Some(notice) = copyright_notice(config) {
+            html.push_str(&format!("<footer>{}</footer>\n", escape_xml(&notice)));
+        }
+
+        html.push_str("<script>\n");
+        html.push_str(SCRIPT);
+        html.push_str("</script>\n");
+        html.push_str("</body>\n</html>\n");
+
+        html
+    }
+
+    fn generate_section_heading(&self, section: &Section, chapter: &Chapter) -> String {
+        let mut html = format!("<header class=\"book-section\" id=\"section-{}\">\n", chapter.first_number);
+        html.push_str(&format!("<h1>{}</h1>\n", escape_xml(&section.title)));
+        if let Some(summary) = chapter.summary() {
+            html.push_str(&format!("<p class=\"summary\">{}</p>\n", escape_xml(&summary)));
+        }
+        for paragraph in section.intro_paragraphs() {
+            html.push_str(&format!("<p>{}</p>\n", escape_xml(paragraph)));
+        }
+        html.push_str("</header>\n");
+        html
+    }
+
+    fn generate_puzzle_section(&self, puzzle: &CrosswordPuzzle, number: usize) -> String {
+        let size = puzzle.grid.len();
+        let mut html = String::new();
+
+        let numbers = puzzle.cell_numbers();
+
+        html.push_str(&format!("<section class=\"puzzle\" id=\"puzzle-{}\">\n", number));
+        html.push_str(&format!("<h2>Puzzle {}</h2>\n", number));
+
+        // Answers are obfuscated so they can't be read straight from the source
+        let key = obfuscation_key(number);
+        let data = json!({
+            "size": size,
+            "key": key,
+            "answers": obfuscate_grid(&puzzle.grid, key),
+            "across": word_list(&puzzle.across_clues),
+            "down": word_list(&puzzle.down_clues),
+        });
+        html.push_str(&format!(
+            "<script type=\"application/json\" class=\"puzzle-data\">{}</script>\n",
+            data
+        ));
+
+        html.push_str("<div class=\"layout\">\n");
+        html.push_str("<div class=\"board\">\n");
+        html.push_str("<table class=\"grid\" tabindex=\"0\">\n");
+        for (y, row) in puzzle.grid.iter().enumerate() {
+            html.push_str("<tr>");
+            for (x, cell) in row.iter().enumerate() {
+                if cell.is_some() {
+                    html.push_str(&format!("<td class=\"cell\" data-i=\"{}\">", y * size + x));
+                    if let Some(num) = numbers[y][x] {
+                        html.push_str(&format!("<span class=\"num\">{}</span>", num));
+                    }
+                    html.push_str("<span class=\"letter\"></span></td>");
+                } else {
+                    html.push_str("<td class=\"block\"></td>");
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<div class=\"controls\">\n");
+        for (action, label) in [
+            ("check-letter", "Check letter"),
+            ("check-word", "Check word"),
+            ("check-puzzle", "Check puzzle"),
+            ("reveal-letter", "Reveal letter"),
+            ("reveal-word", "Reveal word"),
+            ("reveal-puzzle", "Reveal puzzle"),
+            ("clear", "Clear"),
+        ] {
+            html.push_str(&format!(
+                "<button type=\"button\" data-action=\"{}\">{}</button>\n",
+                action, label
+            ));
+        }
+        html.push_str("<p class=\"status\" aria-live=\"polite\"></p>\n");
+        html.push_str("</div>\n");
+        html.push_str("</div>\n");
+
+        html.push_str("<div class=\"clues\">\n");
+        html.push_str(&self.generate_clue_list("Across", "across", &puzzle.across_clues));
+        html.push_str(&self.generate_clue_list("Down", "down", &puzzle.down_clues));
+        html.push_str("</div>\n");
+        html.push_str("</div>\n");
+        html.push_str("</section>\n");
+
+        html
+    }
+
+    fn generate_clue_list(&self, heading: &str, direction: &str, clues: &[Clue]) -> String {
+        let mut html = String::new();
+
+        html.push_str("<div class=\"clue-list\">\n");
+        html.push_str(&format!("<h3>{}</h3>\n<ol>\n", heading));
+        for (idx, clue) in clues.iter().enumerate() {
+            html.push_str(&format!(
+                "<li data-dir=\"{}\" data-word=\"{}\"><b>{}</b> {}</li>\n",
+                direction,
+                idx,
+                clue.number,
+                escape_xml(&clue.clue)
+            ));
+        }
+        html.push_str("</ol>\n</div>\n");
+
+        html
+    }
+}
+
+impl Default for HtmlGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `[x, y, length]` for each entry, in clue order
+fn word_list(clues: &[Clue]) -> Vec<[usize; 3]> {
+    clues.iter()
+        .map(|c| [c.x, c.y, c.word.chars().count()])
+        .collect()
+}
+
+fn obfuscation_key(number: usize) -> u8 {
+    (number.wrapping_mul(97).wrapping_add(41) % 251) as u8
+}
+
+/// Hex string of each cell XORed with a position-dependent mask (blocks are 0)
+fn obfuscate_grid(grid: &[Vec<Option<char>>], key: u8) -> String {
+    grid.iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, cell)| {
+            let byte = cell.map(|c| c as u32 as u8).unwrap_or(0);
+            let mask = (key as usize + i * 31) as u8;
+            format!("{:02x}", byte ^ mask)
+        })
+        .collect()
+}
+
+const STYLE: &str = r#"body { font-family: Georgia, "Times New Roman", serif; margin: 0 auto; max-width: 1100px; padding: 1.5rem; color: #111; }
+h1, h2, h3 { font-family: Helvetica, Arial, sans-serif; }
+.book-title { text-align: center; margin-bottom: 3rem; }
+.toc ol { columns: 4; text-align: left; }
+.toc h2 { font-size: 1.1rem; text-align: left; margin: 1rem 0 0.25rem; }
+.book-section { margin-bottom: 2rem; }
+.book-section .summary { font-style: italic; }
+.puzzle { margin-bottom: 4rem; }
+.layout { display: flex; flex-wrap: wrap; gap: 2rem; align-items: flex-start; }
+.board { flex: 0 0 auto; }
+.grid { border-collapse: collapse; outline: none; user-select: none; }
+.grid td { width: 2rem; height: 2rem; border: 1px solid #333; padding: 0; position: relative; text-align: center; vertical-align: middle; }
+.grid td.block { background: #111; }
+.grid td.cell { cursor: pointer; background: #fff; }
+.grid td.in-word { background: #d6e9ff; }
+.grid td.current { background: #ffd966; }
+.grid td.wrong .letter { color: #c00; text-decoration: line-through; }
+.grid td.revealed .letter { color: #1a55c4; }
+.num { position: absolute; top: 1px; left: 2px; font-size: 0.55rem; font-family: Helvetica, Arial, sans-serif; }
+.letter { font-size: 1.1rem; font-family: Helvetica, Arial, sans-serif; text-transform: uppercase; }
+.controls { margin-top: 0.75rem; max-width: 32rem; }
+.controls button { margin: 0 0.25rem 0.25rem 0; }
+.status { min-height: 1.2em; font-weight: bold; }
+.clues { flex: 1 1 20rem; display: flex; gap: 1.5rem; }
+.clue-list { flex: 1; }
+.clue-list ol { list-style: none; padding: 0; margin: 0; }
+.clue-list li { padding: 0.15rem 0.3rem; cursor: pointer; }
+.clue-list li.active { background: #d6e9ff; }
+footer { margin-top: 3rem; font-size: 0.85rem; text-align: center; color: #555; }
+@media print {
+  body { max-width: none; padding: 0; }
+  .controls, .toc { display: none; }
+  .puzzle, .book-section { page-break-after: always; break-after: page; }
+  .grid td.cell, .grid td.in-word, .grid td.current { background: #fff; }
+  .grid td.block { -webkit-print-color-adjust: exact; print-color-adjust: exact; }
+  .clue-list li.active { background: none; }
+}
+"#;
+
+const SCRIPT: &str = r#"(function () {
+  function decode(hex, key) {
+    var cells = [];
+    for (var i = 0; i < hex.length / 2; i++) {
+      var b = parseInt(hex.substr(i * 2, 2), 16) ^ ((key + i * 31) & 255);
+      cells.push(b === 0 ? null : String.fromCharCode(b));
+    }
+    return cells;
+  }
+
+  function setup(section) {
+    var data = JSON.parse(section.querySelector('.puzzle-data').textContent);
+    var size = data.size;
+    var answers = decode(data.answers, data.key);
+    var entries = answers.map(function () { return ''; });
+    var table = section.querySelector('.grid');
+    var status = section.querySelector('.status');
+    var tds = {};
+    table.querySelectorAll('td.cell').forEach(function (td) { tds[td.dataset.i] = td; });
+
+    var words = { across: [], down: [] };
+    var wordAt = { across: {}, down: {} };
+    ['across', 'down'].forEach(function (dir) {
+      data[dir].forEach(function (w, idx) {
+        var cells = [];
+        for (var k = 0; k < w[2]; k++) {
+          var i = dir === 'across' ? w[1] * size + w[0] + k : (w[1] + k) * size + w[0];
+          cells.push(i);
+          wordAt[dir][i] = idx;
+        }
+        words[dir].push(cells);
+      });
+    });
+
+    var cur = Object.keys(tds).map(Number).sort(function (a, b) { return a - b; })[0];
+    var dir = wordAt.across[cur] !== undefined ? 'across' : 'down';
+
+    function currentWord() {
+      var idx = wordAt[dir][cur];
+      return idx === undefined ? [cur] : words[dir][idx];
+    }
+
+    function render() {
+      var word = currentWord();
+      Object.keys(tds).forEach(function (i) {
+        var td = tds[i];
+        td.querySelector('.letter').textContent = entries[i];
+        td.classList.toggle('current', Number(i) === cur);
+        td.classList.toggle('in-word', word.indexOf(Number(i)) >= 0);
+      });
+      section.querySelectorAll('.clue-list li').forEach(function (li) {
+        li.classList.toggle('active', li.dataset.dir === dir && Number(li.dataset.word) === wordAt[dir][cur]);
+      });
+    }
+
+    function select(i, d) {
+      cur = i;
+      if (d) { dir = d; }
+      if (wordAt[dir][cur] === undefined) { dir = dir === 'across' ? 'down' : 'across'; }
+      render();
+    }
+
+    function step(dx, dy) {
+      var x = cur % size, y = Math.floor(cur / size);
+      while (true) {
+        x += dx; y += dy;
+        if (x < 0 || y < 0 || x >= size || y >= size) { return; }
+        if (answers[y * size + x] !== null) { select(y * size + x); return; }
+      }
+    }
+
+    function advance(delta) {
+      var word = currentWord();
+      var pos = word.indexOf(cur) + delta;
+      if (pos >= 0 && pos < word.length) { select(word[pos]); }
+    }
+
+    function nextWord(delta) {
+      var list = words[dir];
+      var idx = wordAt[dir][cur] === undefined ? 0 : wordAt[dir][cur] + delta;
+      if (idx >= list.length || idx < 0) {
+        dir = dir === 'across' ? 'down' : 'across';
+        list = words[dir];
+        idx = delta > 0 ? 0 : list.length - 1;
+      }
+      if (list[idx]) { select(list[idx][0]); }
+    }
+
+    function mark(cells, reveal) {
+      cells.forEach(function (i) {
+        var td = tds[i];
+        td.classList.remove('wrong');
+        if (reveal) {
+          if (entries[i] !== answers[i]) { entries[i] = answers[i]; td.classList.add('revealed'); }
+        } else if (entries[i] && entries[i] !== answers[i]) {
+          td.classList.add('wrong');
+        }
+      });
+      render();
+      checkSolved();
+    }
+
+    function checkSolved() {
+      var solved = Object.keys(tds).every(function (i) { return entries[i] === answers[i]; });
+      status.textContent = solved ? 'Solved!' : '';
+    }
+
+    table.addEventListener('click', function (e) {
+      var td = e.target.closest('td.cell');
+      if (!td) { return; }
+      var i = Number(td.dataset.i);
+      if (i === cur) { select(i, dir === 'across' ? 'down' : 'across'); } else { select(i); }
+      table.focus();
+    });
+
+    table.addEventListener('keydown', function (e) {
+      var key = e.key;
+      if (/^[a-zA-Z]$/.test(key)) {
+        entries[cur] = key.toUpperCase();
+        tds[cur].classList.remove('wrong');
+        advance(1);
+        render();
+        checkSolved();
+      } else if (key === 'Backspace') {
+        if (entries[cur]) { entries[cur] = ''; } else { advance(-1); entries[cur] = ''; }
+        tds[cur].classList.remove('wrong');
+        render();
+      } else if (key === 'Delete') {
+        entries[cur] = '';
+        render();
+      } else if (key === 'ArrowRight' || key === 'ArrowLeft') {
+        if (dir !== 'across' && wordAt.across[cur] !== undefined) { select(cur, 'across'); } else { step(key === 'ArrowRight' ? 1 : -1, 0); }
+      } else if (key === 'ArrowDown' || key === 'ArrowUp') {
+        if (dir !== 'down' && wordAt.down[cur] !== undefined) { select(cur, 'down'); } else { step(0, key === 'ArrowDown' ? 1 : -1); }
+      } else if (key === 'Tab') {
+        nextWord(e.shiftKey ? -1 : 1);
+      } else if (key === ' ' || key === 'Enter') {
+        select(cur, dir === 'across' ? 'down' : 'across');
+      } else {
+        return;
+      }
+      e.preventDefault();
+    });
+
+    section.querySelectorAll('.clue-list li').forEach(function (li) {
+      li.addEventListener('click', function () {
+        select(words[li.dataset.dir][Number(li.dataset.word)][0], li.dataset.dir);
+        table.focus();
+      });
+    });
+
+    section.querySelectorAll('.controls button').forEach(function (button) {
+      button.addEventListener('click', function () {
+        var all = Object.keys(tds).map(Number);
+        switch (button.dataset.action) {
+          case 'check-letter': mark([cur], false); break;
+          case 'check-word': mark(currentWord(), false); break;
+          case 'check-puzzle': mark(all, false); break;
+          case 'reveal-letter': mark([cur], true); break;
+          case 'reveal-word': mark(currentWord(), true); break;
+          case 'reveal-puzzle': mark(all, true); break;
+          case 'clear':
+            all.forEach(function (i) { entries[i] = ''; tds[i].classList.remove('wrong', 'revealed'); });
+            render();
+            checkSolved();
+            break;
+        }
+        table.focus();
+      });
+    });
+
+    render();
+  }
+
+  document.querySelectorAll('section.puzzle').forEach(setup);
+})();
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_are_obfuscated() {
+        let grid = vec![
+            vec![Some('C'), Some('A'), Some('T')],
+            vec![Some('A'), None, Some('O')],
+            vec![Some('B'), Some('E'), Some('T')],
+        ];
+        let key = obfuscation_key(1);
+        let hex = obfuscate_grid(&grid, key);
+        assert_eq!(hex.len(), 18);
+
+        // Decoding with the same mask as the page script recovers the grid
+        let decoded: Vec<Option<char>> = (0..9)
+            .map(|i| {
+                let byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+                let b = byte ^ (key as usize + i * 31) as u8;
+                if b == 0 { None } else { Some(b as char) }
+            })
+            .collect();
+        let flat: Vec<Option<char>> = grid.into_iter().flatten().collect();
+        assert_eq!(decoded, flat);
+        assert!(!hex.to_uppercase().contains("434154")); // "CAT" in plain hex
+    }
+}
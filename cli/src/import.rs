@@ -0,0 +1,178 @@
+use crossword_core::{parse_ascii_grid, parse_ipuz, parse_puz, CrosswordBook, CrosswordPuzzle, Dictionary};
+use anyhow::{Result, Context};
+use std::cell::Cell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Load every `.puz`, `.ipuz` and `.txt` file in a directory, in file name order.
+/// Entries in text grids without a clue get one from the dictionary, which
+/// is only loaded when a grid needs it. A file that fails to load (a `.puz`
+/// with a bad checksum, say) fails the import, listing every such file,
+/// unless `skip_invalid` is set. Returns the puzzles and how many files
+/// were found.
+pub fn load_puzzles_from_dir(
+    dir: &Path,
+    skip_invalid: bool,
+    load_dict: impl FnOnce() -> Result<Dictionary>,
+) -> Result<(Vec<CrosswordPuzzle>, usize)> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read import directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| is_importable(path))
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        anyhow::bail!("No .puz, .ipuz or .txt files found in {}", dir.display());
+    }
+
+    let dict = if paths.iter().any(|path| needs_dictionary(path)) { Some(load_dict()?) } else { None };
+    let mut puzzles = Vec::new();
+    let mut failures = Vec::new();
+    for path in &paths {
+        match load_puzzle(path, dict.as_ref()) {
+            Ok(puzzle) => puzzles.push(puzzle),
+            Err(error) => failures.push(format!("{:#}", error)),
+        }
+    }
+
+    if !failures.is_empty() && !skip_invalid {
+        anyhow::bail!(
+            "{} of {} files in {} could not be imported (--skip-invalid leaves them out):\n  {}",
+            failures.len(), paths.len(), dir.display(), failures.join("\n  ")
+        );
+    }
+    for failure in &failures {
+        eprintln!("Warning: skipping {}", failure);
+    }
+    if puzzles.is_empty() {
+        anyhow::bail!("No puzzles could be imported from {}: every file failed to load", dir.display());
+    }
+    Ok((puzzles, paths.len()))
+}
+
+/// Load a single puzzle file, choosing the format by extension. Text grids
+/// that leave entries unclued need the dictionary.
+pub fn load_puzzle(path: &Path, dict: Option<&Dictionary>) -> Result<CrosswordPuzzle> {
+    let data = fs::read(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let puzzle = match extension(path).as_deref() {
+        Some("ipuz") => {
+            let json = String::from_utf8_lossy(&data);
+            parse_ipuz(&json).and_then(|doc| doc.to_puzzle())
+        }
+        Some("txt") => {
+            let text = String::from_utf8_lossy(&data);
+            let unclued = Cell::new(false);
+            let puzzle = parse_ascii_grid(&text, |word| match dict {
+                Some(dict) => dict.get_clue(word),
+                None => {
+                    unclued.set(true);
+                    String::new()
+                }
+            });
+            if puzzle.is_ok() && unclued.get() {
+                anyhow::bail!("{}: entries without a clue need a dictionary", path.display());
+            }
+            puzzle
+        }
+        _ => parse_puz(&data).map(|puz| puz.puzzle),
+    };
+
+    puzzle.map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+}
+
+/// Load the puzzles in one file: a puzzle file as for `load_puzzle`, or
+/// JSON holding either a single puzzle or a whole book
+pub fn load_puzzles(path: &Path, load_dict: impl FnOnce() -> Result<Dictionary>) -> Result<Vec<CrosswordPuzzle>> {
+    if extension(path).as_deref() != Some("json") {
+        let dict = if needs_dictionary(path) { Some(load_dict()?) } else { None };
+        return Ok(vec![load_puzzle(path, dict.as_ref())?]);
+    }
+
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if let Ok(book) = serde_json::from_str::<CrosswordBook>(&json) {
+        return Ok(book.puzzles().to_vec());
+    }
+    let puzzle = serde_json::from_str(&json)
+        .with_context(|| format!("{}: not a puzzle or book JSON", path.display()))?;
+    Ok(vec![puzzle])
+}
+
+/// Puzzle files by extension, leaving out the `puzzle-NNN.solution.txt`
+/// answer grids that `export --format txt` writes next to the puzzles
+fn is_importable(path: &Path) -> bool {
+    let is_solution = path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.to_ascii_lowercase().ends_with(".solution.txt"));
+    !is_solution && matches!(extension(path).as_deref(), Some("puz") | Some("ipuz") | Some("txt"))
+}
+
+/// Text grids take missing clues from the dictionary. Other text files
+/// (a README, say) are not grids and fail to load without one.
+fn needs_dictionary(path: &Path) -> bool {
+    if extension(path).as_deref() != Some("txt") {
+        return false;
+    }
+    let Ok(data) = fs::read(path) else {
+        return false;
+    };
+    let unclued = Cell::new(false);
+    let grid = parse_ascii_grid(&String::from_utf8_lossy(&data), |_| {
+        unclued.set(true);
+        String::new()
+    });
+    grid.is_ok() && unclued.get()
+}
+
+pub fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::filled_sample_puzzle;
+    use crossword_core::{IpuzDocument, IpuzMetadata};
+
+    #[test]
+    fn test_load_puzzles_from_dir() {
+        let dir = std::env::temp_dir().join(format!("crossword-import-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let puzzle = filled_sample_puzzle(|w| format!("Clue for {}", w));
+        let ipuz = IpuzDocument::from_puzzle(&puzzle, IpuzMetadata::default()).to_json().unwrap();
+        fs::write(dir.join("puzzle-001.ipuz"), ipuz).unwrap();
+        fs::write(dir.join("puzzle-001.solution.txt"), "CAT\nA#O\nBET\n").unwrap();
+        fs::write(dir.join("puzzle-002.puz"), "not a puz file").unwrap();
+        fs::write(dir.join("puzzle-003.txt"), "CAT\nA#O\nBET\n\nACROSS:\n1. Pet\n3. Wager\nDOWN:\n1. Taxi\n2. Tot\n").unwrap();
+        fs::write(dir.join("README.txt"), "Puzzles from the 2019 archive\n").unwrap();
+
+        // A damaged file fails the import and every bad file is named
+        let no_dictionary = || -> Result<Dictionary> { anyhow::bail!("dictionary loaded") };
+        let error = format!("{:#}", load_puzzles_from_dir(&dir, false, no_dictionary).unwrap_err());
+        assert!(error.starts_with("2 of 4 files"), "{}", error);
+        assert!(error.contains("README.txt") && error.contains("puzzle-002.puz"), "{}", error);
+
+        // Neither the fully clued grid nor the README needs the dictionary
+        let (puzzles, found) = load_puzzles_from_dir(&dir, true, no_dictionary).unwrap();
+        assert_eq!((puzzles.len(), found), (2, 4));
+        assert_eq!(puzzles[0].grid, puzzle.grid);
+        assert_eq!(puzzles[1].across_clues[1].clue, "Wager");
+
+        // An unclued grid does
+        fs::write(dir.join("puzzle-004.txt"), "CAT\nA#O\nBET\n").unwrap();
+        assert_eq!(load_puzzles_from_dir(&dir, true, no_dictionary).unwrap_err().to_string(), "dictionary loaded");
+        fs::remove_file(dir.join("puzzle-004.txt")).unwrap();
+
+        fs::remove_file(dir.join("puzzle-001.ipuz")).unwrap();
+        fs::remove_file(dir.join("puzzle-003.txt")).unwrap();
+        assert!(load_puzzles_from_dir(&dir, true, no_dictionary).unwrap_err().to_string().starts_with("No puzzles could be imported"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+use crossword_core::{BookConfig, Clue, CrosswordPuzzle};
+use crate::export::{copyright_notice, escape_xml};
+
+/// Writes puzzles as Crossword Compiler / Crossword Nexus `.jpz` XML
+pub struct JpzGenerator {}
+
+impl JpzGenerator {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn generate_puzzle(&self, puzzle: &CrosswordPuzzle, config: &BookConfig, number: usize) -> String {
+        let mut xml = String::new();
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<crossword-compiler-applet xmlns=\"http://crossword.info/xml/crossword-compiler\">\n");
+        xml.push_str("<rectangular-puzzle xmlns=\"http://crossword.info/xml/rectangular-puzzle\" alphabet=\"ABCDEFGHIJKLMNOPQRSTUVWXYZ\">\n");
+
+        xml.push_str(&self.generate_metadata(config, number));
+
+        xml.push_str("<crossword>\n");
+        xml.push_str(&self.generate_grid(puzzle));
+
+        // Word ids: across words first, then down, in clue order
+        let down_offset = puzzle.across_clues.len();
+        for (idx, clue) in puzzle.across_clues.iter().enumerate() {
+            xml.push_str(&self.generate_word(idx + 1, clue, true));
+        }
+        for (idx, clue) in puzzle.down_clues.iter().enumerate() {
+            xml.push_str(&self.generate_word(down_offset + idx + 1, clue, false));
+        }
+
+        xml.push_str(&self.generate_clues("Across", &puzzle.across_clues, 1));
+        xml.push_str(&self.generate_clues("Down", &puzzle.down_clues, down_offset + 1));
+
+        xml.push_str("</crossword>\n");
+        xml.push_str("</rectangular-puzzle>\n");
+        xml.push_str("</crossword-compiler-applet>\n");
+
+        xml
+    }
+
+    fn generate_metadata(&self, config: &BookConfig, number: usize) -> String {
+        let mut xml = String::new();
+
+        xml.push_str("<metadata>\n");
+        xml.push_str(&format!(
+            "<title>{} - Puzzle {}</title>\n",
+            escape_xml(&config.title),
+            number
+        ));
+        if let Some(ref author) = config.author {
+            xml.push_str(&format!("<creator>{}</creator>\n", escape_xml(author)));
+        }
+        if let Some(notice) = copyright_notice(config) {
+            xml.push_str(&format!("<copyright>{}</copyright>\n", escape_xml(&notice)));
+        }
+        if let Some(ref publisher) = config.publisher {
+            xml.push_str(&format!("<publisher>{}</publisher>\n", escape_xml(publisher)));
+        }
+        if let Some(ref description) = config.description {
+            xml.push_str(&format!("<description>{}</description>\n", escape_xml(description)));
+        }
+        xml.push_str("</metadata>\n");
+
+        xml
+    }
+
+    fn generate_grid(&self, puzzle: &CrosswordPuzzle) -> String {
+        let size = puzzle.grid.len();
+        let mut xml = String::new();
+
+        let numbers = puzzle.cell_numbers();
+
+        xml.push_str(&format!("<grid width=\"{}\" height=\"{}\">\n", size, size));
+        xml.push_str("<grid-look numbering-scheme=\"normal\" cell-size-in-pixels=\"26\"/>\n");
+
+        // JPZ coordinates are 1-based
+        for (row, cells) in puzzle.grid.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                match cell {
+                    Some(letter) => {
+                        xml.push_str(&format!(
+                            "<cell x=\"{}\" y=\"{}\" solution=\"{}\"",
+                            col + 1,
+                            row + 1,
+                            escape_xml(&letter.to_string())
+                        ));
+                        if let Some(num) = numbers[row][col] {
+                            xml.push_str(&format!(" number=\"{}\"", num));
+                        }
+                        xml.push_str("/>\n");
+                    }
+                    None => {
+                        xml.push_str(&format!(
+                            "<cell x=\"{}\" y=\"{}\" type=\"block\"/>\n",
+                            col + 1,
+                            row + 1
+                        ));
+                    }
+                }
+            }
+        }
+
+        xml.push_str("</grid>\n");
+
+        xml
+    }
+
+    fn generate_word(&self, id: usize, clue: &Clue, across: bool) -> String {
+        let len = clue.word.chars().count();
+        let mut xml = format!("<word id=\"{}\">\n", id);
+
+        for i in 0..len {
+            let (x, y) = if across {
+                (clue.x + i, clue.y)
+            } else {
+                (clue.x, clue.y + i)
+            };
+            xml.push_str(&format!("<cells x=\"{}\" y=\"{}\"/>\n", x + 1, y + 1));
+        }
+
+        xml.push_str("</word>\n");
+        xml
+    }
+
+    fn generate_clues(&self, heading: &str, clues: &[Clue], first_word_id: usize) -> String {
+        let mut xml = String::new();
+
+        xml.push_str("<clues ordering=\"normal\">\n");
+        xml.push_str(&format!("<title><b>{}</b></title>\n", heading));
+        for (idx, clue) in clues.iter().enumerate() {
+            xml.push_str(&format!(
+                "<clue word=\"{}\" number=\"{}\" format=\"{}\">{}</clue>\n",
+                first_word_id + idx,
+                clue.number,
+                clue.word.chars().count(),
+                escape_xml(&clue.clue)
+            ));
+        }
+        xml.push_str("</clues>\n");
+
+        xml
+    }
+}
+
+impl Default for JpzGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::filled_sample_puzzle;
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+    use std::collections::HashMap;
+
+    /// Element paths (e.g. "crossword-compiler-applet/rectangular-puzzle/crossword/grid/cell")
+    /// with the attributes of each occurrence
+    fn element_paths(xml: &str) -> Vec<(String, HashMap<String, String>)> {
+        let mut reader = Reader::from_str(xml);
+        let mut stack: Vec<String> = Vec::new();
+        let mut elements = Vec::new();
+
+        loop {
+            match reader.read_event().expect("well-formed XML") {
+                Event::Start(e) => {
+                    stack.push(String::from_utf8_lossy(e.name().as_ref()).to_string());
+                    elements.push((stack.join("/"), attributes(&e)));
+                }
+                Event::Empty(e) => {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    elements.push((format!("{}/{}", stack.join("/"), name), attributes(&e)));
+                }
+                Event::End(_) => {
+                    stack.pop();
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        assert!(stack.is_empty(), "unclosed elements: {:?}", stack);
+        elements
+    }
+
+    fn attributes(e: &quick_xml::events::BytesStart) -> HashMap<String, String> {
+        e.attributes()
+            .map(|a| {
+                let a = a.unwrap();
+                (
+                    String::from_utf8_lossy(a.key.as_ref()).to_string(),
+                    a.unescape_value().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_jpz_schema_shape() {
+        let mut config = BookConfig::new("Test Book".to_string(), 3);
+        config.author = Some("Jane Doe".to_string());
+        config.copyright_year = Some("2024".to_string());
+
+        let xml = JpzGenerator::new().generate_puzzle(&filled_sample_puzzle(|w| format!("Clue for {} & co", w)), &config, 7);
+        let elements = element_paths(&xml);
+        let root = "crossword-compiler-applet/rectangular-puzzle";
+        let count = |path: &str| elements.iter().filter(|(p, _)| p == path).count();
+
+        assert_eq!(count("crossword-compiler-applet"), 1);
+        assert_eq!(count(&format!("{}/metadata/title", root)), 1);
+        assert_eq!(count(&format!("{}/metadata/creator", root)), 1);
+        assert_eq!(count(&format!("{}/metadata/copyright", root)), 1);
+        assert_eq!(count(&format!("{}/crossword/grid", root)), 1);
+        assert_eq!(count(&format!("{}/crossword/grid/cell", root)), 9);
+        assert_eq!(count(&format!("{}/crossword/word", root)), 4);
+        assert_eq!(count(&format!("{}/crossword/word/cells", root)), 12);
+        assert_eq!(count(&format!("{}/crossword/clues", root)), 2);
+        assert_eq!(count(&format!("{}/crossword/clues/clue", root)), 4);
+
+        let cells: Vec<_> = elements.iter()
+            .filter(|(p, _)| p.ends_with("/grid/cell"))
+            .map(|(_, attrs)| attrs)
+            .collect();
+        assert_eq!(cells[0].get("number").map(String::as_str), Some("1"));
+        assert_eq!(cells[0].get("solution").map(String::as_str), Some("C"));
+        assert_eq!(cells[4].get("type").map(String::as_str), Some("block"));
+
+        // Every clue references an existing word id
+        let word_ids: Vec<_> = elements.iter()
+            .filter(|(p, _)| p.ends_with("/crossword/word"))
+            .map(|(_, attrs)| attrs["id"].clone())
+            .collect();
+        for (_, attrs) in elements.iter().filter(|(p, _)| p.ends_with("/clues/clue")) {
+            assert!(word_ids.contains(&attrs["word"]));
+            assert_eq!(attrs["format"], "3");
+        }
+
+        assert!(xml.contains("Clue for CAT &amp; co"));
+        assert!(xml.contains("<copyright>© 2024 Jane Doe</copyright>"));
+    }
+}
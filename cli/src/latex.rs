@@ -1,5 +1,5 @@
-use crossword_core::CrosswordPuzzle;
-use crate::book::{CrosswordBook, KdpFormat};
+use crossword_core::{drop_cap, BookConfig, Chapter, CrosswordBook, CrosswordPuzzle, Margins, Section, INTRODUCTION, INTRODUCTION_TITLES};
+use crate::stats::BookStats;
 use anyhow::Result;
 
 pub struct LatexGenerator {}
@@ -9,7 +9,8 @@ impl LatexGenerator {
         Self {}
     }
 
-    pub fn generate_document(&self, book: &CrosswordBook) -> Result<String> {
+    /// The book, followed by a statistics appendix when `stats` is given
+    pub fn generate_document(&self, book: &CrosswordBook, stats: Option<&BookStats>) -> Result<String> {
         let mut latex = String::new();
         
         // Preamble
@@ -30,7 +31,7 @@ impl LatexGenerator {
         
         // Table of contents
         latex.push_str("\\clearpage\n");
-        latex.push_str(&self.generate_toc(book.puzzle_count()));
+        latex.push_str(&self.generate_toc(&book.chapters(), stats.is_some()));
         
         // Main matter (arabic numerals, starts on odd/right page)
         latex.push_str("\\cleardoublepage\n");
@@ -39,23 +40,33 @@ impl LatexGenerator {
         // Introduction page (will be page 1, odd/right)
         latex.push_str(&self.generate_introduction(book.config()));
         
-        // Generate puzzles with facing pages (clues on left, grid on right)
-        for (idx, puzzle) in book.puzzles().iter().enumerate() {
-            latex.push_str(&self.generate_puzzle_spread(puzzle, idx + 1)?);
+        // Generate puzzles with facing pages (clues on left, grid on right),
+        // each section opening with its own heading page
+        for chapter in book.chapters() {
+            if let Some(section) = chapter.section {
+                latex.push_str(&self.generate_section_opener(section, &chapter));
+            }
+            for (offset, puzzle) in chapter.puzzles.iter().enumerate() {
+                latex.push_str(&self.generate_puzzle_spread(puzzle, chapter.first_number + offset)?);
+            }
         }
         
         // Answer key
         latex.push_str("\\cleardoublepage\n");
         latex.push_str("\\chapter*{Answer Key}\n");
         latex.push_str("\\addcontentsline{toc}{chapter}{Answer Key}\n\n");
-        latex.push_str(&self.generate_answer_key(book.puzzles())?);
+        latex.push_str(&self.generate_answer_key(&book.chapters())?);
+        
+        if let Some(stats) = stats {
+            latex.push_str(&self.generate_stats_appendix(stats));
+        }
         
         latex.push_str("\\end{document}\n");
         
         Ok(latex)
     }
 
-    fn generate_preamble(&self, config: &crate::book::BookConfig) -> String {
+    fn generate_preamble(&self, config: &BookConfig) -> String {
         let (page_width, page_height, margins) = self.get_kdp_dimensions(config);
         
         format!(
@@ -105,35 +116,12 @@ impl LatexGenerator {
         )
     }
 
-    fn get_kdp_dimensions(&self, config: &crate::book::BookConfig) -> (f32, f32, Margins) {
+    fn get_kdp_dimensions(&self, config: &BookConfig) -> (f32, f32, Margins) {
         let trim = &config.trim_size;
-        
-        match config.kdp_format {
-            KdpFormat::Paperback => {
-                // No bleed for text-only puzzle books
-                // Margins based on KDP requirements (assuming 100-400 pages)
-                let margins = Margins {
-                    top: 0.75,
-                    bottom: 0.75,
-                    inner: 0.625,  // Gutter for binding
-                    outer: 0.5,
-                };
-                (trim.width, trim.height, margins)
-            }
-            KdpFormat::Ebook => {
-                // Ebook: simpler margins
-                let margins = Margins {
-                    top: 0.5,
-                    bottom: 0.5,
-                    inner: 0.5,
-                    outer: 0.5,
-                };
-                (trim.width, trim.height, margins)
-            }
-        }
+        (trim.width, trim.height, config.margins())
     }
 
-    fn generate_kdp_title_page(&self, config: &crate::book::BookConfig) -> Result<String> {
+    fn generate_kdp_title_page(&self, config: &BookConfig) -> Result<String> {
         let mut latex = String::new();
         
         latex.push_str("\\thispagestyle{empty}\n");
@@ -183,7 +171,7 @@ impl LatexGenerator {
         Ok(latex)
     }
 
-    fn generate_copyright_page(&self, config: &crate::book::BookConfig) -> String {
+    fn generate_copyright_page(&self, config: &BookConfig) -> String {
         let mut latex = String::new();
         
         latex.push_str("\\thispagestyle{empty}\n");
@@ -240,7 +228,7 @@ impl LatexGenerator {
         latex
     }
 
-    fn generate_toc(&self, puzzle_count: usize) -> String {
+    fn generate_toc(&self, chapters: &[Chapter], with_stats: bool) -> String {
         let mut latex = String::new();
         
         latex.push_str("\\thispagestyle{empty}\n");
@@ -251,15 +239,27 @@ impl LatexGenerator {
         
         latex.push_str("\\begin{flushleft}\n");
         latex.push_str("Introduction \\dotfill ~1\n\n");
-        latex.push_str(&format!("Puzzles (1--{}) \\dotfill ~2\n\n", puzzle_count));
+        for chapter in chapters {
+            let last = chapter.first_number + chapter.puzzles.len() - 1;
+            match chapter.section {
+                Some(section) => latex.push_str(&format!(
+                    "{} ({}) \\dotfill ~\\pageref{{section:{}}}\n\n",
+                    escape_latex(&section.title), number_range(chapter.first_number, last), chapter.first_number
+                )),
+                None => latex.push_str(&format!("Puzzles ({}) \\dotfill ~2\n\n", number_range(chapter.first_number, last))),
+            }
+        }
         latex.push_str("Answer Key \\dotfill ~\\pageref{answerkey}\n\n");
+        if with_stats {
+            latex.push_str("Book Statistics \\dotfill ~\\pageref{bookstats}\n\n");
+        }
         latex.push_str("\\end{flushleft}\n");
         latex.push_str("\\clearpage\n\n");
         
         latex
     }
 
-    fn generate_introduction(&self, config: &crate::book::BookConfig) -> String {
+    fn generate_introduction(&self, config: &BookConfig) -> String {
         let mut latex = String::new();
         
         latex.push_str("\\chapter*{Introduction}\n\n");
@@ -268,14 +268,22 @@ impl LatexGenerator {
         latex.push_str("\\setlength{\\parindent}{1.5em}\n");
         latex.push_str("\\setlength{\\parskip}{0.8em}\n\n");
         
-        // First paragraph with drop cap
-        latex.push_str("\\lettrine[lines=3,lhang=0.1,loversize=0.15]{C}{rossword} puzzles have captivated minds for over a century, beginning with Arthur Wynne's \\textit{Word-Cross} puzzle published in the \\textit{New York World} on December 21, 1913. What started as a simple diamond-shaped grid has evolved into one of the world's most beloved pastimes, challenging millions of solvers daily.\n\n");
-        
-        latex.push_str("The beauty of a well-crafted crossword lies in the delicate balance between challenge and satisfaction. Each puzzle is a carefully constructed lattice of interlocking words, where every letter serves double duty, connecting both across and down entries. The best puzzles reward both knowledge and wordplay, offering that satisfying ``aha!'' moment when a difficult clue finally clicks.\n\n");
+        let italics = |text: &str| {
+            INTRODUCTION_TITLES.iter().fold(escape_latex(text), |text, title| text.replace(title, &format!("\\textit{{{}}}", title)))
+        };
         
-        latex.push_str("This collection is designed to provide hours of engaging entertainment. Whether you're a seasoned cruciverbalist or a curious beginner, these puzzles offer a perfect blend of vocabulary, general knowledge, and lateral thinking.\n\n");
+        // First paragraph with drop cap; lettrine sets the rest of the first word in small caps
+        let (initial, opening) = drop_cap(INTRODUCTION[0]);
+        let (first_word, rest) = opening.split_once(' ').unwrap_or((opening, ""));
+        latex.push_str(&format!(
+            "\\lettrine[lines=3,lhang=0.1,loversize=0.15]{{{}}}{{{}}} {}\n\n",
+            escape_latex(initial), escape_latex(first_word), italics(rest)
+        ));
         
-        latex.push_str("Each puzzle is printed with the grid on the right page and clues on the left, allowing you to see both simultaneously as you solve. Take your time, work in pencil, and remember: every puzzle has a solution, and the journey to finding it is half the fun.\n\n");
+        for paragraph in &INTRODUCTION[1..] {
+            latex.push_str(&italics(paragraph));
+            latex.push_str("\n\n");
+        }
         
         latex.push_str("\\vspace{1.5cm}\n\n");
         
@@ -299,6 +307,40 @@ impl LatexGenerator {
         latex
     }
 
+    fn generate_section_opener(&self, section: &Section, chapter: &Chapter) -> String {
+        let mut latex = String::new();
+        let title = escape_latex(&section.title);
+
+        // Heading on a right-hand page, so the section's first spread starts on the left
+        latex.push_str("\\cleardoublepage\n");
+        latex.push_str(&format!("\\chapter*{{{}}}\n", title));
+        latex.push_str(&format!("\\addcontentsline{{toc}}{{chapter}}{{{}}}\n", title));
+        latex.push_str(&format!("\\label{{section:{}}}\n\n", chapter.first_number));
+
+        let count = chapter.puzzles.len();
+        latex.push_str(&format!(
+            "{{\\large {} {} \\quad {}$\\times${} grids}}\n\n",
+            if count == 1 { "Puzzle" } else { "Puzzles" },
+            number_range(chapter.first_number, chapter.first_number + count - 1),
+            section.grid_size,
+            section.grid_size
+        ));
+
+        if section.intro.is_some() {
+            latex.push_str("\\vspace{1cm}\n\n");
+            latex.push_str("\\setlength{\\parskip}{0.8em}\n");
+            for paragraph in section.intro_paragraphs() {
+                latex.push_str(&escape_latex(paragraph));
+                latex.push_str("\n\n");
+            }
+            latex.push_str("\\setlength{\\parskip}{0pt}\n\n");
+        }
+
+        latex.push_str("\\clearpage\n\n");
+
+        latex
+    }
+
     fn generate_puzzle_spread(&self, puzzle: &CrosswordPuzzle, number: usize) -> Result<String> {
         let mut latex = String::new();
         
@@ -427,11 +469,28 @@ impl LatexGenerator {
         Ok(latex)
     }
 
-    fn generate_answer_key(&self, puzzles: &[CrosswordPuzzle]) -> Result<String> {
+    fn generate_answer_key(&self, chapters: &[Chapter]) -> Result<String> {
         let mut latex = String::new();
         
         latex.push_str("\\label{answerkey}\n\n");
         
+        // Each section starts a new page under its own heading
+        for (chapter_idx, chapter) in chapters.iter().enumerate() {
+            if chapter_idx > 0 {
+                latex.push_str("\\clearpage\n\n");
+            }
+            if let Some(section) = chapter.section {
+                latex.push_str(&format!("\\section*{{{}}}\n\n", escape_latex(&section.title)));
+            }
+            latex.push_str(&self.generate_answer_pages(chapter.puzzles, chapter.first_number)?);
+        }
+        
+        Ok(latex)
+    }
+
+    fn generate_answer_pages(&self, puzzles: &[CrosswordPuzzle], first_number: usize) -> Result<String> {
+        let mut latex = String::new();
+        
         // 4 puzzles per page, 2x2 grid
         for (page_idx, chunk) in puzzles.chunks(4).enumerate() {
             if page_idx > 0 {
@@ -439,7 +498,7 @@ impl LatexGenerator {
             }
             
             for (chunk_idx, puzzle) in chunk.iter().enumerate() {
-                let puzzle_num = page_idx * 4 + chunk_idx + 1;
+                let puzzle_num = first_number + page_idx * 4 + chunk_idx;
                 
                 if chunk_idx % 2 == 0 {
                     latex.push_str("\\noindent\\begin{minipage}[t]{0.48\\textwidth}\n");
@@ -505,13 +564,42 @@ impl LatexGenerator {
         
         Ok(latex)
     }
-}
 
-struct Margins {
-    top: f32,
-    bottom: f32,
-    inner: f32,
-    outer: f32,
+    fn generate_stats_appendix(&self, stats: &BookStats) -> String {
+        let mut latex = String::new();
+
+        latex.push_str("\\cleardoublepage\n");
+        latex.push_str("\\chapter*{Book Statistics}\n");
+        latex.push_str("\\addcontentsline{toc}{chapter}{Book Statistics}\n");
+        latex.push_str("\\label{bookstats}\n\n");
+        latex.push_str(&format!("{} puzzles with {} answers.\n\n", stats.puzzles, stats.words));
+
+        let mut density = vec![("Density".to_string(), "Puzzles".to_string())];
+        density.extend(stats.density.iter().map(|b| (
+            format!("{:.0}--{:.0}\\%", b.from * 100.0, b.to * 100.0),
+            b.puzzles.to_string(),
+        )));
+        latex.push_str(&stats_table(&density));
+
+        let mut lengths = vec![("Word length".to_string(), "Answers".to_string())];
+        lengths.extend(stats.word_lengths.iter().map(|(length, count)| (length.to_string(), count.to_string())));
+        latex.push_str(&stats_table(&lengths));
+
+        if !stats.repeated_answers.is_empty() {
+            let mut answers = vec![("Most repeated answers".to_string(), "Uses".to_string())];
+            answers.extend(stats.repeated_answers.iter().map(|r| (escape_latex(&r.text), r.count.to_string())));
+            latex.push_str(&stats_table(&answers));
+        }
+
+        if let Some(ref t) = stats.generation_time_ms {
+            latex.push_str(&format!(
+                "Generation time per puzzle: median {:.1}\\,s, 90th percentile {:.1}\\,s, slowest {:.1}\\,s.\n\n",
+                t.p50 as f64 / 1000.0, t.p90 as f64 / 1000.0, t.max as f64 / 1000.0
+            ));
+        }
+
+        latex
+    }
 }
 
 impl Default for LatexGenerator {
@@ -520,6 +608,29 @@ impl Default for LatexGenerator {
     }
 }
 
+/// A two-column table with a bold header row
+fn stats_table(rows: &[(String, String)]) -> String {
+    let mut latex = String::from("\\begin{center}\n\\begin{tabular}{lr}\n\\hline\n");
+    for (idx, (label, value)) in rows.iter().enumerate() {
+        if idx == 0 {
+            latex.push_str(&format!("\\textbf{{{}}} & \\textbf{{{}}} \\\\\n\\hline\n", label, value));
+        } else {
+            latex.push_str(&format!("{} & {} \\\\\n", label, value));
+        }
+    }
+    latex.push_str("\\hline\n\\end{tabular}\n\\end{center}\n\n");
+    latex
+}
+
+/// "3--7", or just "3" for a single puzzle
+fn number_range(first: usize, last: usize) -> String {
+    if first == last {
+        first.to_string()
+    } else {
+        format!("{}--{}", first, last)
+    }
+}
+
 fn escape_latex(s: &str) -> String {
     s.replace('\\', "\\textbackslash{}")
         .replace('&', "\\&")
@@ -1,38 +1,97 @@
-use clap::Parser;
-use crossword_core::{Dictionary, solve_with_iterations, CrosswordPuzzle};
-use indicatif::{ProgressBar, ProgressStyle};
+use clap::{Args, Parser, Subcommand};
+use crossword_core::{BookConfig, ClueDatabase, CrosswordBook, Dictionary, DictionaryFormat, Difficulty, KdpFormat, PdfGenerator, Section, TextStyle, TextOptions, TrimSize, render_puzzle_text, render_solution_text};
 use std::fs;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
-use rand::seq::SliceRandom;
 
 mod latex;
-mod book;
 mod cover;
+mod import;
+mod export;
+mod jpz;
+mod html;
+mod raster;
+mod epub;
+mod docx;
+mod clues;
+mod clue_picker;
+mod validate;
+mod config;
+mod checkpoint;
+mod batch;
+mod used_words;
+mod stats;
+mod serve;
 
 use latex::LatexGenerator;
-use book::{BookConfig, CrosswordBook};
 use cover::CoverGenerator;
+use config::{ConfigFile, SectionConfig};
+use checkpoint::{Checkpoint, CheckpointHeader};
+use batch::{BatchRun, BatchState, Budget};
+use used_words::UsedWords;
+use clue_picker::CluePicker;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Family-friendly word filter used when no --allowlist is given
+const DEFAULT_ALLOWLIST: &str = include_str!("../../wasm/clean_allowlist.txt");
 
 #[derive(Parser, Debug)]
 #[command(name = "crossword-cli")]
 #[command(about = "Generate LaTeX crossword puzzle books", long_about = None)]
-struct Args {
-    /// Number of puzzles to generate
-    #[arg(short, long, default_value = "10")]
-    count: usize,
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate (or import) puzzles and save them as book JSON
+    Generate(Box<GenerateArgs>),
+    /// Typeset a book as LaTeX, PDF, HTML, EPUB, DOCX or text
+    Render(RenderArgs),
+    /// Write one file per puzzle: ipuz, jpz, html, svg, png or txt
+    Export(ExportArgs),
+    /// Check a book's grids, numbering and clues
+    Validate(ValidateArgs),
+    /// Fill a KDP cover template for a book
+    Cover(CoverArgs),
+    /// Inspect the dictionary: stats, word lookups and word lists
+    Dict(DictArgs),
+    /// Write every clue of a book to CSV for editing
+    ExportClues(ExportCluesArgs),
+    /// Merge edited clues from CSV or DOCX back into a book
+    ImportClues(ImportCluesArgs),
+    /// Regenerate, move, delete or insert puzzles in a saved book
+    Puzzle(PuzzleArgs),
+    /// Report density, word lengths, repeats, crossings and generation times
+    Stats(StatsArgs),
+    /// Serve a local HTTP API for generating, validating and exporting puzzles
+    Serve(ServeArgs),
+}
 
-    /// Output LaTeX file path
-    #[arg(short, long, default_value = "crossword_book.tex")]
+#[derive(Args, Debug)]
+struct GenerateArgs {
+    /// Book settings file (TOML); flags given here override it
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Number of puzzles to generate (default: 10)
+    #[arg(short, long)]
+    count: Option<usize>,
+
+    /// Output book JSON path
+    #[arg(short, long, default_value = "crossword_book.json")]
     output: PathBuf,
 
-    /// Grid size (NxN)
-    #[arg(short, long, default_value = "16")]
-    size: usize,
+    /// Grid size (NxN, default: 16)
+    #[arg(short, long)]
+    size: Option<usize>,
 
-    /// Book title
-    #[arg(short, long, default_value = "Crossword Puzzle Book")]
-    title: String,
+    /// Book title (default: "Crossword Puzzle Book")
+    #[arg(short, long)]
+    title: Option<String>,
 
     /// Author name
     #[arg(short, long)]
@@ -58,7 +117,7 @@ struct Args {
     #[arg(short, long)]
     description: Option<String>,
 
-    /// Cover subtitle (replaces "PUZZLES" in template)
+    /// Subtitle (also replaces "PUZZLES" in cover templates)
     #[arg(long)]
     subtitle: Option<String>,
 
@@ -66,44 +125,400 @@ struct Args {
     #[arg(long)]
     seed: Option<u64>,
 
-    /// Automatically compile PDF with pdflatex
-    #[arg(long)]
-    compile: bool,
-
     /// Number of parallel threads (default: number of CPU cores)
     #[arg(short = 'j', long)]
     jobs: Option<usize>,
 
+    #[command(flatten)]
+    dictionary: DictionaryArgs,
+
+    /// KDP format: paperback or ebook (default: paperback)
+    #[arg(long)]
+    kdp_format: Option<String>,
+
+    /// Trim size for paperback (default: 8x10, options: 5x8, 5.5x8.5, 6x9, 7x10, 8x10)
+    #[arg(long)]
+    trim_size: Option<String>,
+
+    /// Build the book from a directory of .puz/.ipuz/.txt files instead of generating puzzles
+    #[arg(long)]
+    import: Option<PathBuf>,
+
+    /// With --import, leave out files that fail to load instead of stopping
+    #[arg(long, requires = "import")]
+    skip_invalid: bool,
+
+    /// JSONL file each finished puzzle is appended to (default: <output>.checkpoint.jsonl)
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// Continue an interrupted run from its checkpoint, skipping finished puzzles
+    #[arg(long)]
+    resume: bool,
+
+    /// Stop after this many attempts in total, failed ones included (default: 10 per puzzle)
+    #[arg(long)]
+    max_attempts: Option<usize>,
+
+    /// Start no new attempts after this long, e.g. 90m or 2h
+    #[arg(long)]
+    time_limit: Option<String>,
+
+    /// Use each answer at most this many times in the book, counting --used-words
+    #[arg(long)]
+    max_repeats: Option<usize>,
+
+    /// Registry of answers used by earlier volumes; read first, then updated with this book's answers
+    #[arg(long)]
+    used_words: Option<PathBuf>,
+}
+
+/// Where answers and clues come from, for every command that needs them
+#[derive(Args, Debug, Clone, Default)]
+struct DictionaryArgs {
+    /// Dictionary file: Oxford text, a word list, or WORD<TAB>clue TSV
+    /// (default: the embedded dictionary, in builds that include it)
+    #[arg(long, env = "CROSSWORD_DICTIONARY")]
+    dictionary: Option<PathBuf>,
+
+    /// Format of --dictionary: oxford, words or tsv (default: detected)
+    #[arg(long)]
+    dictionary_format: Option<String>,
+
     /// Path to word allowlist file (one word per line, filters dictionary)
     #[arg(long)]
     allowlist: Option<PathBuf>,
 
-    /// KDP format: paperback or ebook (default: paperback)
-    #[arg(long, default_value = "paperback")]
-    kdp_format: String,
+    /// Leave out words with these usage labels, e.g. archaic,derog,colloq
+    #[arg(long, value_delimiter = ',')]
+    exclude_labels: Vec<String>,
 
-    /// Trim size for paperback (default: 8x10, options: 5x8, 5.5x8.5, 6x9, 7x10, 8x10)
-    #[arg(long, default_value = "8x10")]
-    trim_size: String,
+    /// Leave out the words in this file (one word per line)
+    #[arg(long)]
+    blocklist: Option<PathBuf>,
+
+    /// Clue database checked before dictionary definitions: TSV
+    /// (answer, clue, difficulty, source) or a .json array of entries
+    #[arg(long)]
+    clues: Option<PathBuf>,
 
-    /// Path to cover template SVG (uses kdp-format to determine type)
+    /// Prefer clues of this difficulty from --clues: easy, medium or hard
     #[arg(long)]
-    cover_template: Option<PathBuf>,
+    clue_difficulty: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct RenderArgs {
+    /// Book JSON saved by `generate`
+    book: PathBuf,
 
-    /// Generate cover file (requires cover-template)
+    /// Output format: latex, pdf, html, epub, docx or text
+    #[arg(short, long, default_value = "latex")]
+    format: String,
+
+    /// Output file (default: the book path with the format's extension; text goes to stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// PDF engine for --format pdf: pdflatex (writes the .tex too) or native (built-in, no TeX needed)
+    #[arg(long, default_value = "pdflatex")]
+    pdf_engine: String,
+
+    /// Text style for --format text: unicode, ascii or ansi (default: ansi on a terminal, otherwise unicode)
     #[arg(long)]
-    generate_cover: bool,
+    text_style: Option<String>,
 
-    /// Use color interior for spine width calculation (affects cover)
+    /// Append a book statistics chapter (latex, and pdf with pdflatex)
+    #[arg(long)]
+    stats_appendix: bool,
+}
+
+#[derive(Args, Debug)]
+struct ExportArgs {
+    /// Book JSON saved by `generate`
+    book: PathBuf,
+
+    /// Per-puzzle format: ipuz, jpz, html, svg, png or txt
+    #[arg(short, long)]
+    format: String,
+
+    /// Output directory (default: <book>-<format>/ next to the book)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// PNG resolution in dots per inch (1 to 1200)
+    #[arg(long, default_value = "150")]
+    dpi: u32,
+
+    /// White space around PNG grids in inches (0 to 2)
+    #[arg(long, default_value = "0.1")]
+    padding: f32,
+
+    /// Text stamped faintly across PNG grids (e.g. "SAMPLE")
+    #[arg(long)]
+    watermark: Option<String>,
+
+    /// Use ASCII-only characters in txt files instead of Unicode box drawing
+    #[arg(long)]
+    ascii: bool,
+}
+
+#[derive(Args, Debug)]
+struct ValidateArgs {
+    /// Book JSON to check
+    book: PathBuf,
+
+    /// Fail on warnings as well as errors
+    #[arg(long)]
+    strict: bool,
+}
+
+#[derive(Args, Debug)]
+struct CoverArgs {
+    /// Book JSON saved by `generate`
+    book: PathBuf,
+
+    /// Path to cover template SVG (the book's KDP format determines the type)
+    #[arg(long)]
+    template: Option<PathBuf>,
+
+    /// Book settings file (TOML) to take the template and interior from
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Output SVG path (default: <book>.cover.svg)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Cover subtitle (default: the book's subtitle, or "PUZZLES")
+    #[arg(long)]
+    subtitle: Option<String>,
+
+    /// Use color interior for spine width calculation
     #[arg(long)]
     color_interior: bool,
 }
 
+#[derive(Args, Debug)]
+struct DictArgs {
+    /// Words to look up
+    words: Vec<String>,
+
+    #[command(flatten)]
+    dictionary: DictionaryArgs,
+
+    /// List words of this length
+    #[arg(long)]
+    length: Option<usize>,
+
+    /// List words matching a pattern, with ? for any letter (e.g. C?T)
+    #[arg(long)]
+    pattern: Option<String>,
+
+    /// Maximum number of words to list
+    #[arg(long, default_value = "50")]
+    limit: usize,
+}
+
+#[derive(Args, Debug)]
+struct ExportCluesArgs {
+    /// Book JSON saved by `generate`
+    book: PathBuf,
+
+    /// CSV output path (default: <book>.clues.csv)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ImportCluesArgs {
+    /// Book JSON to update
+    book: PathBuf,
+
+    /// Edited clues: a .csv from `export-clues` or a .docx from `render --format docx`
+    clues: PathBuf,
+
+    /// Check the edits without writing the book
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+struct ServeArgs {
+    /// Address to listen on. Keep it on localhost unless the port is firewalled
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: String,
+
+    #[command(flatten)]
+    dictionary: DictionaryArgs,
+
+    /// Puzzles generated at the same time (default: CPU cores)
+    #[arg(short, long)]
+    jobs: Option<usize>,
+
+    /// Generation jobs that may be queued or running before new ones are refused
+    #[arg(long, default_value = "32")]
+    queue: usize,
+}
+
+#[derive(Args, Debug)]
+struct StatsArgs {
+    /// Book JSON saved by `generate`
+    book: PathBuf,
+
+    /// Output format: text or json
+    #[arg(short, long, default_value = "text")]
+    format: String,
+
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct PuzzleArgs {
+    #[command(subcommand)]
+    command: PuzzleCommand,
+}
+
+/// Puzzle numbers are 1-based, as printed in the book. Every puzzle not
+/// named is written back unchanged.
+#[derive(Subcommand, Debug)]
+enum PuzzleCommand {
+    /// Replace a puzzle with a newly generated one of the same size
+    Regenerate(RegenerateArgs),
+    /// Move a puzzle to another position
+    Move(MoveArgs),
+    /// Delete puzzles
+    Delete(DeleteArgs),
+    /// Insert puzzles from a .puz, .ipuz, .txt or JSON file
+    Insert(InsertArgs),
+}
+
+#[derive(Args, Debug)]
+struct RegenerateArgs {
+    /// Book JSON to update
+    book: PathBuf,
+
+    /// Puzzle to replace
+    number: usize,
+
+    /// Answers the new puzzle must not use, comma-separated
+    #[arg(long, value_delimiter = ',')]
+    exclude: Vec<String>,
+
+    /// Random seed for reproducibility
+    #[arg(long)]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    dictionary: DictionaryArgs,
+
+    /// Give up after this many failed attempts
+    #[arg(long, default_value_t = batch::DEFAULT_ATTEMPTS_PER_PUZZLE)]
+    max_attempts: usize,
+
+    /// Where to save the book (default: overwrite it)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct MoveArgs {
+    /// Book JSON to update
+    book: PathBuf,
+
+    /// Puzzle to move
+    from: usize,
+
+    /// Its new number
+    to: usize,
+
+    /// Where to save the book (default: overwrite it)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct DeleteArgs {
+    /// Book JSON to update
+    book: PathBuf,
+
+    /// Puzzles to delete
+    #[arg(required = true)]
+    numbers: Vec<usize>,
+
+    /// Where to save the book (default: overwrite it)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct InsertArgs {
+    /// Book JSON to update
+    book: PathBuf,
+
+    /// Puzzle file: .puz, .ipuz, .txt, or JSON with one puzzle or a whole book
+    file: PathBuf,
+
+    /// Number of the first inserted puzzle (default: after the last puzzle)
+    #[arg(long)]
+    at: Option<usize>,
+
+    /// Dictionary for clues missing from .txt grids
+    #[command(flatten)]
+    dictionary: DictionaryArgs,
+
+    /// Where to save the book (default: overwrite it)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Generate(args) => generate(*args),
+        Command::Render(args) => render(args),
+        Command::Export(args) => export(args),
+        Command::Validate(args) => validate(args),
+        Command::Cover(args) => cover(args),
+        Command::Dict(args) => dict(args),
+        Command::ExportClues(args) => export_clues(args),
+        Command::ImportClues(args) => import_clues(args),
+        Command::Puzzle(args) => match args.command {
+            PuzzleCommand::Regenerate(args) => regenerate_puzzle(args),
+            PuzzleCommand::Move(args) => move_puzzle(args),
+            PuzzleCommand::Delete(args) => delete_puzzles(args),
+            PuzzleCommand::Insert(args) => insert_puzzles(args),
+        },
+        Command::Stats(args) => stats(args),
+        Command::Serve(args) => serve(args),
+    }
+}
+
+fn generate(args: GenerateArgs) -> Result<()> {
+    let file = match args.config {
+        Some(ref path) => {
+            println!("Using config: {}", path.display());
+            ConfigFile::load(path)?
+        }
+        None => ConfigFile::default(),
+    };
+    let sections = plan_sections(&args, &file)?;
+    let mut config = book_config(&args, &file, sections[0].size)?;
+    if !file.sections.is_empty() && args.import.is_none() {
+        config.sections = sections.iter()
+            .map(|section| Section {
+                title: section.title.clone(),
+                intro: section.intro.clone(),
+                grid_size: section.size,
+            })
+            .collect();
+    }
 
     // Set number of rayon threads if specified
-    if let Some(jobs) = args.jobs {
+    if let Some(jobs) = args.jobs.or(file.generate.jobs) {
         rayon::ThreadPoolBuilder::new()
             .num_threads(jobs)
             .build_global()
@@ -113,276 +528,891 @@ fn main() -> Result<()> {
         println!("Using {} parallel threads (CPU cores)", rayon::current_num_threads());
     }
 
-    // Set random seed if provided
-    if let Some(seed) = args.seed {
-        use rand::SeedableRng;
-        rand::rngs::StdRng::seed_from_u64(seed);
-        println!("Using random seed: {}", seed);
+    let dict_args = DictionaryArgs {
+        dictionary: args.dictionary.dictionary.clone().or(file.generate.dictionary.clone()),
+        dictionary_format: args.dictionary.dictionary_format.clone().or(file.generate.dictionary_format.clone()),
+        allowlist: args.dictionary.allowlist.clone().or(file.generate.allowlist.clone()),
+        exclude_labels: if args.dictionary.exclude_labels.is_empty() {
+            file.generate.exclude_labels.clone().unwrap_or_default()
+        } else {
+            args.dictionary.exclude_labels.clone()
+        },
+        blocklist: args.dictionary.blocklist.clone().or(file.generate.blocklist.clone()),
+        clues: args.dictionary.clues.clone().or(file.generate.clues.clone()),
+        clue_difficulty: args.dictionary.clue_difficulty.clone().or(file.generate.clue_difficulty.clone()),
+    };
+
+    let mut book = CrosswordBook::new(config);
+
+    if let Some(ref import_dir) = args.import {
+        println!("\nImporting puzzles from {}...", import_dir.display());
+        let (puzzles, found) = import::load_puzzles_from_dir(import_dir, args.skip_invalid, || load_dictionary(&dict_args))?;
+        println!("Imported {} of {} files", puzzles.len(), found);
+        for puzzle in puzzles {
+            book.add_puzzle(puzzle);
+        }
+        save_book(&book, &args.output)?;
+        return Ok(());
     }
 
-    println!("Initializing dictionary...");
-    let dict = if let Some(ref allowlist_path) = args.allowlist {
-        let allowlist_text = fs::read_to_string(allowlist_path)
-            .context("Failed to read allowlist file")?;
-        println!("Using custom allowlist: {}", allowlist_path.display());
-        Dictionary::with_allowlist(Some(&allowlist_text))
+    let dict = load_dictionary(&dict_args)?;
+
+    // Check every option before touching the checkpoint, so a bad flag
+    // doesn't leave one behind that blocks the next run
+    let time_limit = match args.time_limit {
+        Some(ref limit) => Some(batch::parse_duration(limit)?),
+        None => file.generate.time_limit,
+    };
+
+    // Answer dedupe is opt-in: with it, a puzzle's words depend on which
+    // puzzles finished before it, so seeded runs are no longer reproducible
+    let max_repeats = args.max_repeats.or(file.generate.max_repeats);
+    if max_repeats == Some(0) {
+        anyhow::bail!("--max-repeats must be at least 1");
+    }
+    let used_words_path = args.used_words.clone().or(file.generate.used_words.clone());
+    let mut used_words = if max_repeats.is_some() || used_words_path.is_some() {
+        let used = match used_words_path {
+            Some(ref path) => UsedWords::load(path, max_repeats)?,
+            None => UsedWords::new(max_repeats),
+        };
+        println!("Avoiding repeated answers ({} already used)", used.len());
+        Some(used)
     } else {
-        // Use default embedded allowlist
-        let default_allowlist = include_str!("../../wasm/clean_allowlist.txt");
-        println!("Using default allowlist");
-        Dictionary::with_allowlist(Some(default_allowlist))
+        None
     };
-    let stats = dict.stats();
-    println!("Dictionary loaded: {} words (filtered)", stats.word_count);
 
-    let mut config = BookConfig::new(args.title.clone(), args.size);
-    config.subtitle = args.subtitle.clone();
-    config.author = args.author;
-    config.publisher = args.publisher;
-    config.edition = args.edition;
-    config.isbn = args.isbn;
-    config.copyright_year = args.copyright;
-    config.description = args.description;
-    
-    // Set KDP format
-    config.kdp_format = match args.kdp_format.to_lowercase().as_str() {
-        "ebook" => book::KdpFormat::Ebook,
-        _ => book::KdpFormat::Paperback,
+    let checkpoint_path = args.checkpoint.clone()
+        .unwrap_or_else(|| args.output.with_extension("checkpoint.jsonl"));
+    let requested_seed = args.seed.or(file.generate.seed);
+    let header = CheckpointHeader { seed: requested_seed.unwrap_or_else(rand::random) };
+
+    let (checkpoint, contents) = if args.resume {
+        let (checkpoint, contents) = Checkpoint::resume(&checkpoint_path, &header)?;
+        if requested_seed.is_some_and(|seed| seed != contents.header.seed) {
+            anyhow::bail!("--seed {} does not match the seed {} of checkpoint {}",
+                          header.seed, contents.header.seed, checkpoint_path.display());
+        }
+        println!("Resuming from {}: {} puzzles already done", checkpoint_path.display(), contents.puzzles.len());
+        (checkpoint, contents)
+    } else {
+        let checkpoint = Checkpoint::create(&checkpoint_path, &header)?;
+        (checkpoint, checkpoint::CheckpointContents { header, puzzles: Vec::new(), failures: Vec::new() })
     };
-    
-    // Set trim size
-    config.trim_size = book::TrimSize::from_string(&args.trim_size)?;
-    
-    // Clone values we'll need later for cover generation
-    let title_for_cover = config.title.clone();
-    let author_for_cover = config.author.clone();
-    let subtitle_for_cover = args.subtitle.clone();
-    let trim_size_for_cover = config.trim_size.clone();
-    let kdp_format_for_cover = config.kdp_format.clone();
+    let seed = contents.header.seed;
+    println!("Using random seed: {}", seed);
 
-    let mut book = CrosswordBook::new(config);
+    let mut next_attempt = HashMap::new();
+    for failure in &contents.failures {
+        let next = next_attempt.entry((failure.section, failure.slot)).or_insert(0);
+        *next = (*next).max(failure.attempt + 1);
+    }
 
-    println!("\nGenerating {} puzzles of size {}x{} in parallel...", args.count, args.size, args.size);
-    let pb = ProgressBar::new(args.count as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("=>-"),
-    );
+    // Finished puzzles by (section, slot), so the book's order doesn't
+    // depend on which thread finished first
+    let mut finished = BTreeMap::new();
+    for entry in contents.puzzles {
+        let Some(section) = sections.get(entry.section) else {
+            anyhow::bail!("Checkpoint has puzzles for section {}, but this run has {} sections", entry.section + 1, sections.len());
+        };
+        if entry.size != section.size {
+            anyhow::bail!("Checkpoint puzzles for section {} are {}x{}, but this run asks for {}x{}",
+                          entry.section + 1, entry.size, entry.size, section.size, section.size);
+        }
+        if entry.slot < section.count {
+            finished.entry((entry.section, entry.slot)).or_insert(entry.puzzle);
+        }
+    }
 
-    // Generate puzzles in parallel
-    use rayon::prelude::*;
-    let puzzles: Vec<_> = (0..args.count)
-        .into_par_iter()
-        .filter_map(|i| {
-            match generate_crossword(&dict, args.size) {
-                Ok(puzzle) => {
-                    pb.inc(1);
-                    Some(puzzle)
-                }
-                Err(e) => {
-                    eprintln!("\nWarning: Failed to generate puzzle {}: {}", i + 1, e);
-                    pb.inc(1);
-                    None
+    let requested: usize = sections.iter().map(|s| s.count).sum();
+    let remaining = requested - finished.len();
+    let budget = Budget {
+        max_attempts: Some(args.max_attempts.or(file.generate.max_attempts)
+            .unwrap_or(remaining * batch::DEFAULT_ATTEMPTS_PER_PUZZLE)),
+        time_limit,
+    };
+
+    if let Some(ref mut used) = used_words {
+        for puzzle in finished.values() {
+            used.add_puzzle(puzzle);
+        }
+    }
+    let used_words = used_words.map(Mutex::new);
+
+    let run = BatchRun {
+        dict: &dict,
+        seed,
+        checkpoint: Mutex::new(checkpoint),
+        state: BatchState::new(budget),
+        next_attempt,
+        used_words,
+    };
+
+    // Sections are generated in order so puzzle numbers run on across them
+    for (idx, section) in sections.iter().enumerate() {
+        let pending: Vec<usize> = (0..section.count)
+            .filter(|slot| !finished.contains_key(&(idx, *slot)))
+            .collect();
+        for (slot, puzzle) in run.generate_section(idx, section, &pending, sections.len() > 1)? {
+            finished.insert((idx, slot), puzzle);
+        }
+    }
+
+    let stats = run.state.stats();
+    println!("\n{}", stats.report());
+    println!("Total time: {:.1}s", run.state.elapsed().as_secs_f64());
+
+    // Clues are picked once every puzzle is in, in book order, so an
+    // answer's clues rotate the same way however the threads finished
+    let mut picker = CluePicker::new(&dict, book.config());
+    for ((section, _), mut puzzle) in finished {
+        picker.assign(&mut puzzle);
+        book.add_section_puzzle(section, puzzle);
+    }
+
+    if book.puzzle_count() == 0 {
+        anyhow::bail!("No puzzles were generated successfully");
+    }
+
+    save_book(&book, &args.output)?;
+
+    let checkpoint = run.checkpoint.into_inner().unwrap();
+    if book.puzzle_count() < requested {
+        // Keep the checkpoint so --resume can fill the rest with new seeds
+        anyhow::bail!(
+            "Budget used up with {} of {} puzzles generated. Run again with --resume to generate the rest (checkpoint: {})",
+            book.puzzle_count(), requested, checkpoint.path().display()
+        );
+    }
+
+    if let Some(ref path) = used_words_path {
+        // Record this book's answers for the next volume
+        let mut registry = UsedWords::load(path, None)?;
+        for puzzle in book.puzzles() {
+            registry.add_puzzle(puzzle);
+        }
+        registry.save(path)?;
+        println!("✅ Used words: {} answers in {}", registry.len(), path.display());
+    }
+
+    let checkpoint_path = checkpoint.path().to_path_buf();
+    checkpoint.remove()?;
+    println!("Removed checkpoint {}", checkpoint_path.display());
+    Ok(())
+}
+
+fn save_book(book: &CrosswordBook, output: &Path) -> Result<()> {
+    book.save_to_file(output)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    println!("\n✅ Book JSON: {} ({} puzzles)", output.display(), book.puzzle_count());
+    println!("To typeset: crossword-cli render {}", output.display());
+    Ok(())
+}
+
+/// What to generate: the config's sections, or one run from --count/--size
+fn plan_sections(args: &GenerateArgs, file: &ConfigFile) -> Result<Vec<SectionConfig>> {
+    if !file.sections.is_empty() {
+        if args.count.is_some() || args.size.is_some() {
+            anyhow::bail!("--count and --size cannot be used with a config that defines sections");
+        }
+        return Ok(file.sections.clone());
+    }
+    Ok(vec![SectionConfig {
+        title: "Puzzles".to_string(),
+        intro: None,
+        count: args.count.or(file.generate.count).unwrap_or(10),
+        size: args.size.or(file.generate.size).unwrap_or(16),
+    }])
+}
+
+/// Book metadata from the flags, falling back to the config file
+fn book_config(args: &GenerateArgs, file: &ConfigFile, grid_size: usize) -> Result<BookConfig> {
+    let title = args.title.clone().or(file.title.clone())
+        .unwrap_or_else(|| "Crossword Puzzle Book".to_string());
+
+    let mut config = BookConfig::new(title, grid_size);
+    config.subtitle = args.subtitle.clone().or(file.subtitle.clone());
+    config.author = args.author.clone().or(file.author.clone());
+    config.publisher = args.publisher.clone().or(file.publisher.clone());
+    config.edition = args.edition.clone().or(file.edition.clone());
+    config.isbn = args.isbn.clone().or(file.isbn.clone());
+    config.copyright_year = args.copyright.clone().or(file.copyright.clone());
+    config.description = args.description.clone().or(file.description.clone());
+
+    if let Some(kdp_format) = match args.kdp_format {
+        Some(ref format) => Some(KdpFormat::from_string(format).map_err(anyhow::Error::msg)?),
+        None => file.kdp_format,
+    } {
+        config.kdp_format = kdp_format;
+    }
+    if let Some(trim_size) = match args.trim_size {
+        Some(ref size) => Some(TrimSize::from_string(size).map_err(anyhow::Error::msg)?),
+        None => file.trim_size,
+    } {
+        config.trim_size = trim_size;
+    }
+
+    Ok(config)
+}
+
+fn render(args: RenderArgs) -> Result<()> {
+    let book = load_book(&args.book)?;
+    let format = args.format.to_lowercase();
+    let default_output = |extension: &str| args.book.with_extension(extension);
+    let latex_output = matches!(format.as_str(), "latex" | "tex")
+        || (format == "pdf" && args.pdf_engine.eq_ignore_ascii_case("pdflatex"));
+    if args.stats_appendix && !latex_output {
+        anyhow::bail!("--stats-appendix is only supported for LaTeX output (-f latex, or -f pdf with pdflatex)");
+    }
+    let stats = args.stats_appendix.then(|| stats::BookStats::new(&book));
+
+    match format.as_str() {
+        "latex" | "tex" => {
+            let output = args.output.unwrap_or_else(|| default_output("tex"));
+            write_latex(&book, stats.as_ref(), &output)?;
+            println!("To compile: pdflatex {}", output.display());
+        }
+        "pdf" => {
+            let output = args.output.unwrap_or_else(|| default_output("pdf"));
+            compile(&book, stats.as_ref(), &output, &args.pdf_engine)?;
+        }
+        "html" => {
+            let output = args.output.unwrap_or_else(|| default_output("html"));
+            let html = html::HtmlGenerator::new().generate_book_page(&book);
+            fs::write(&output, html)
+                .context("Failed to write HTML book")?;
+            println!("✅ HTML book: {}", output.display());
+        }
+        "epub" => {
+            let output = args.output.unwrap_or_else(|| default_output("epub"));
+            let epub = epub::EpubGenerator::new().generate_document(&book)?;
+            fs::write(&output, epub)
+                .context("Failed to write EPUB")?;
+            println!("✅ EPUB: {}", output.display());
+        }
+        "docx" => {
+            let output = args.output.unwrap_or_else(|| default_output("docx"));
+            let docx = docx::DocxGenerator::new().generate_document(&book)?;
+            fs::write(&output, docx)
+                .context("Failed to write DOCX")?;
+            println!("✅ DOCX: {}", output.display());
+        }
+        "text" | "txt" => {
+            let style = match args.text_style {
+                Some(ref style) => style.parse::<TextStyle>().map_err(anyhow::Error::msg)?,
+                None if args.output.is_none() && std::io::stdout().is_terminal() => TextStyle::Ansi,
+                None => TextStyle::Unicode,
+            };
+            let text = render_book_text(&book, style);
+            match args.output {
+                Some(ref output) => {
+                    fs::write(output, text)
+                        .with_context(|| format!("Failed to write {}", output.display()))?;
+                    println!("✅ Text: {}", output.display());
                 }
+                None => print!("{}", text),
             }
-        })
-        .collect();
+        }
+        other => anyhow::bail!("Invalid render format: {}. Use latex, pdf, html, epub, docx or text", other),
+    }
 
-    pb.finish_with_message(format!("Complete! {} successful, {} failed", 
-                                   puzzles.len(), 
-                                   args.count - puzzles.len()));
+    Ok(())
+}
 
-    if puzzles.is_empty() {
-        anyhow::bail!("No puzzles were generated successfully");
+fn export(args: ExportArgs) -> Result<()> {
+    let book = load_book(&args.book)?;
+    let format = args.format.to_lowercase();
+    let dir = args.output.clone().unwrap_or_else(|| {
+        let stem = args.book.file_stem().unwrap_or_default().to_string_lossy();
+        args.book.with_file_name(format!("{}-{}", stem, format))
+    });
+
+    let written = match format.as_str() {
+        "ipuz" => export::export_ipuz(&book, &dir)?,
+        "jpz" => export::export_jpz(&book, &dir)?,
+        "html" => export::export_html(&book, &dir)?,
+        "svg" => export::export_svg(&book, &dir)?,
+        "png" => {
+            let options = raster::PngOptions {
+                dpi: args.dpi,
+                padding: args.padding,
+                watermark: args.watermark.clone(),
+                ..Default::default()
+            };
+            export::export_png(&book, &dir, &options)?
+        }
+        "txt" | "text" => {
+            let style = if args.ascii { TextStyle::Ascii } else { TextStyle::Unicode };
+            export::export_txt(&book, &dir, style)?
+        }
+        other => anyhow::bail!("Invalid export format: {}. Use ipuz, jpz, html, svg, png or txt", other),
+    };
+
+    println!("✅ {}: {} files in {}", format, written.len(), dir.display());
+    Ok(())
+}
+
+fn validate(args: ValidateArgs) -> Result<()> {
+    let book = load_book(&args.book)?;
+    let report = validate::validate_book(&book);
+
+    for error in &report.errors {
+        println!("error: {}", error);
+    }
+    for warning in &report.warnings {
+        println!("warning: {}", warning);
     }
 
-    // Add all puzzles to book
-    for puzzle in puzzles {
-        book.add_puzzle(puzzle);
+    let failed = !report.is_ok() || (args.strict && !report.warnings.is_empty());
+    println!(
+        "\n{} {}: {} puzzles, {} errors, {} warnings",
+        if failed { "❌" } else { "✅" },
+        args.book.display(),
+        book.puzzle_count(),
+        report.errors.len(),
+        report.warnings.len()
+    );
+
+    if failed {
+        anyhow::bail!("Validation failed");
     }
+    Ok(())
+}
 
-    println!("\nGenerating LaTeX document...");
-    let latex_gen = LatexGenerator::new();
-    let latex_content = latex_gen.generate_document(&book)
-        .context("Failed to generate LaTeX document")?;
+fn cover(args: CoverArgs) -> Result<()> {
+    let book = load_book(&args.book)?;
+    let file = match args.config {
+        Some(ref path) => ConfigFile::load(path)?,
+        None => ConfigFile::default(),
+    };
+    let template = args.template.clone().or(file.cover.template)
+        .context("No cover template: pass --template or set [cover] template in the config")?;
+    let color_interior = args.color_interior || file.cover.color_interior;
+    let config = book.config();
+    let is_paperback = matches!(config.kdp_format, KdpFormat::Paperback);
 
-    fs::write(&args.output, latex_content)
-        .context("Failed to write output file")?;
+    // Calculate actual page count:
+    // - Front matter: ~4-6 pages (title, copyright, TOC, intro)
+    // - Each section: a heading page, plus a blank to keep spreads facing
+    // - Each puzzle: 2 pages (clues + grid)
+    // - Answer key: puzzles/4 pages (4 per page) per section + any remainder
+    let chapters = book.chapters();
+    let front_matter = 6;
+    let section_pages = config.sections.len() * 2;
+    let puzzle_pages = book.puzzle_count() * 2;
+    let answer_key_pages: usize = chapters.iter().map(|c| c.puzzles.len().div_ceil(4)).sum();
+    let total_pages = front_matter + section_pages + puzzle_pages + answer_key_pages;
+
+    let cover_gen = CoverGenerator::new(
+        total_pages,
+        config.trim_size.width,
+        config.trim_size.height,
+    );
 
-    println!("\n✅ LaTeX: {}", args.output.display());
+    let subtitle = args.subtitle.as_deref()
+        .or(config.subtitle.as_deref())
+        .unwrap_or("PUZZLES");
+    let author = config.author.as_deref().unwrap_or("");
 
-    // Generate cover if requested
-    if args.generate_cover {
-        println!("\nGenerating cover...");
-        
-        if let Some(template) = args.cover_template.as_ref() {
-            let is_paperback = matches!(kdp_format_for_cover, book::KdpFormat::Paperback);
-            
-            // Calculate actual page count:
-            // - Front matter: ~4-6 pages (title, copyright, TOC, intro)
-            // - Each puzzle: 2 pages (clues + grid)
-            // - Answer key: puzzles/4 pages (4 per page) + any remainder
-            let puzzle_count = book.puzzle_count();
-            let front_matter = 6;
-            let puzzle_pages = puzzle_count * 2;
-            let answer_key_pages = (puzzle_count + 3) / 4; // Round up
-            let total_pages = front_matter + puzzle_pages + answer_key_pages;
-            
-            let cover_gen = CoverGenerator::new(
-                total_pages,
-                trim_size_for_cover.width,
-                trim_size_for_cover.height,
-            );
-            
-            let cover_svg = if is_paperback {
-                cover_gen.generate_paperback_cover(
-                    &template.to_string_lossy(),
-                    &title_for_cover,
-                    subtitle_for_cover.as_deref().unwrap_or("PUZZLES"),
-                    author_for_cover.as_deref().unwrap_or(""),
-                    args.color_interior,
-                )?
+    let cover_svg = if is_paperback {
+        cover_gen.generate_paperback_cover(
+            &template.to_string_lossy(),
+            &config.title,
+            subtitle,
+            author,
+            color_interior,
+        )?
+    } else {
+        cover_gen.generate_ebook_cover(
+            &template.to_string_lossy(),
+            &config.title,
+            subtitle,
+            author,
+        )?
+    };
+
+    let cover_path = args.output.unwrap_or_else(|| args.book.with_extension("cover.svg"));
+    fs::write(&cover_path, cover_svg)?;
+    println!("✅ Cover: {}", cover_path.display());
+    Ok(())
+}
+
+fn dict(args: DictArgs) -> Result<()> {
+    let dict = load_dictionary(&args.dictionary)?;
+    let stats = dict.stats();
+    println!(
+        "Average length {:.1}, longest {}",
+        stats.avg_word_length, stats.max_word_length
+    );
+
+    let mut by_length = std::collections::BTreeMap::new();
+    for word in dict.get_words() {
+        *by_length.entry(word.chars().count()).or_insert(0usize) += 1;
+    }
+    let histogram: Vec<String> = by_length.iter().map(|(len, n)| format!("{}:{}", len, n)).collect();
+    println!("Words by length: {}", histogram.join(" "));
+
+    for word in &args.words {
+        let upper = word.to_uppercase();
+        if dict.get_words().contains(&upper) {
+            let labels = dict.get_labels(&upper);
+            if labels.is_empty() {
+                println!("\n{}  ✓ {}", upper, dict.get_clue(&upper));
             } else {
-                cover_gen.generate_ebook_cover(
-                    &template.to_string_lossy(),
-                    &title_for_cover,
-                    subtitle_for_cover.as_deref().unwrap_or("PUZZLES"),
-                    author_for_cover.as_deref().unwrap_or(""),
-                )?
-            };
-            
-            let cover_path = args.output.with_extension("cover.svg");
-            fs::write(&cover_path, cover_svg)?;
-            println!("✅ Cover: {}", cover_path.display());
+                println!("\n{}  ✓ {}  [{}]", upper, dict.get_clue(&upper), labels.join(", "));
+            }
+            for sense in dict.get_clues(&upper).iter().skip(1) {
+                println!("    also: {}", sense);
+            }
         } else {
-            eprintln!("⚠️  No cover template provided. Use --cover-template");
+            println!("\n{}  ✗ not in word list", upper);
         }
     }
 
-    if args.compile {
-        match compile_pdf(&args.output) {
-            Ok(_) => println!("🎉 Done!"),
-            Err(e) => {
-                eprintln!("\n⚠️  PDF failed: {}", e);
-                eprintln!("But .tex file created successfully");
-                return Err(e);
-            }
+    let pattern: Option<Vec<char>> = args.pattern.as_ref().map(|p| p.to_uppercase().chars().collect());
+    let length = pattern.as_ref().map(|p| p.len()).or(args.length);
+    if let Some(length) = length {
+        let matches: Vec<&String> = dict.get_words().iter()
+            .filter(|w| w.chars().count() == length)
+            .filter(|w| pattern.as_ref().is_none_or(|p| {
+                w.chars().zip(p).all(|(c, &p)| p == '?' || p == c)
+            }))
+            .collect();
+
+        println!("\n{} matching words{}", matches.len(),
+                 if matches.len() > args.limit { format!(" (showing {})", args.limit) } else { String::new() });
+        for word in matches.iter().take(args.limit) {
+            println!("  {}", word);
         }
-    } else {
-        println!("To compile: pdflatex {}", args.output.display());
     }
 
     Ok(())
 }
 
-fn generate_crossword(dict: &Dictionary, size: usize) -> Result<CrosswordPuzzle> {
-    let all_words = dict.get_words();
-    
-    // Filter suitable words
-    let suitable: Vec<String> = all_words.iter()
-        .filter(|w| w.len() >= 3 && w.len() <= size)
-        .cloned()
-        .collect();
-    
-    // Group by length
-    let mut by_length: std::collections::HashMap<usize, Vec<String>> = std::collections::HashMap::new();
-    for word in suitable {
-        by_length.entry(word.len()).or_insert_with(Vec::new).push(word);
+fn export_clues(args: ExportCluesArgs) -> Result<()> {
+    let book = load_book(&args.book)?;
+    let output = args.output.unwrap_or_else(|| args.book.with_extension("clues.csv"));
+
+    let records = book.clue_records();
+    fs::write(&output, clues::write_clues_csv(&records)?)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    println!("✅ Clues: {} rows in {}", records.len(), output.display());
+    Ok(())
+}
+
+fn import_clues(args: ImportCluesArgs) -> Result<()> {
+    let mut book = load_book(&args.book)?;
+    let data = fs::read(&args.clues)
+        .with_context(|| format!("Failed to read {}", args.clues.display()))?;
+
+    let records = match import::extension(&args.clues).as_deref() {
+        Some("csv") => clues::read_clues_csv(&data)?,
+        Some("docx") => docx::read_clue_records(&data)?,
+        _ => anyhow::bail!("Unsupported clue file {}. Use .csv or .docx", args.clues.display()),
+    };
+    let changed = book.merge_clues(&records).map_err(anyhow::Error::msg)?;
+
+    if args.dry_run {
+        println!("✅ {} clues checked, {} would change", records.len(), changed);
+        return Ok(());
     }
-    
-    // Determine max words based on size
-    let max_words = match size {
-        s if s <= 8 => 80,
-        s if s <= 10 => 120,
-        s if s <= 12 => 150,
-        s if s <= 15 => 130,
-        s if s <= 20 => 100,
-        _ => 100,
+
+    book.save_to_file(&args.book)
+        .with_context(|| format!("Failed to write {}", args.book.display()))?;
+    println!("✅ Merged {} clues ({} changed) into {}", records.len(), changed, args.book.display());
+    Ok(())
+}
+
+fn stats(args: StatsArgs) -> Result<()> {
+    let book = load_book(&args.book)?;
+    let stats = stats::BookStats::new(&book);
+
+    let report = match args.format.to_lowercase().as_str() {
+        "text" | "txt" => stats.to_text(),
+        "json" => serde_json::to_string_pretty(&stats)? + "\n",
+        other => anyhow::bail!("Invalid stats format: {}. Use text or json", other),
     };
-    
-    let mut words = Vec::new();
-    
-    // Select words with length distribution
-    for len in 3..=size.min(15) {
-        if let Some(len_words) = by_length.get_mut(&len) {
-            len_words.shuffle(&mut rand::thread_rng());
-            
-            let proportion = if len <= 5 {
-                0.70
-            } else if len <= 8 {
-                0.25
-            } else {
-                0.05
-            };
-            
-            let count = ((max_words as f32 * proportion) / 4.0) as usize;
-            words.extend(len_words.iter().take(count.max(8)).cloned());
-            
-            if words.len() >= max_words {
-                break;
+    match args.output {
+        Some(ref output) => {
+            fs::write(output, report)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            println!("✅ Stats: {}", output.display());
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+fn serve(args: ServeArgs) -> Result<()> {
+    if args.jobs == Some(0) || args.queue == 0 {
+        anyhow::bail!("--jobs and --queue must be at least 1");
+    }
+    let loopback = args.bind.parse::<std::net::SocketAddr>().map(|addr| addr.ip().is_loopback())
+        .unwrap_or_else(|_| args.bind.starts_with("localhost:"));
+    if !loopback {
+        eprintln!("Warning: {} is reachable from other machines. The API has no authentication", args.bind);
+    }
+
+    let dict = load_dictionary(&args.dictionary)?;
+    let mut pool = rayon::ThreadPoolBuilder::new();
+    if let Some(jobs) = args.jobs {
+        pool = pool.num_threads(jobs);
+    }
+    let service = serve::Service {
+        dict,
+        queue: serve::JobQueue::new(args.queue),
+        pool: pool.build().context("Failed to start worker threads")?,
+    };
+    println!("{} workers, up to {} queued jobs", service.pool.current_num_threads(), args.queue);
+    serve::run(service, &args.bind)
+}
+
+fn regenerate_puzzle(args: RegenerateArgs) -> Result<()> {
+    let mut book = load_book(&args.book)?;
+    let idx = puzzle_index(&book, args.number)?;
+    let size = book.puzzles()[idx].grid.len();
+
+    // Steer away from answers already in the rest of the book
+    let mut used = UsedWords::new(None);
+    for (other, puzzle) in book.puzzles().iter().enumerate() {
+        if other != idx {
+            used.add_puzzle(puzzle);
+        }
+    }
+    for word in &args.exclude {
+        used.exclude(word.trim());
+    }
+
+    let dict = load_dictionary(&args.dictionary)?;
+    let seed = args.seed.unwrap_or_else(rand::random);
+    println!("Regenerating puzzle {} ({}x{}) with seed {}...", args.number, size, size, seed);
+
+    let (mut puzzle, failed) = batch::generate_with_retries(&dict, size, seed, args.max_attempts, Some(&used))
+        .with_context(|| format!("Puzzle {} not regenerated", args.number))?;
+
+    let mut picker = CluePicker::new(&dict, book.config());
+    for (other, existing) in book.puzzles().iter().enumerate() {
+        if other != idx {
+            picker.record(existing);
+        }
+    }
+    picker.assign(&mut puzzle);
+
+    let words = puzzle.across_clues.len() + puzzle.down_clues.len();
+    book.replace_puzzle(idx, puzzle).map_err(anyhow::Error::msg)?;
+    println!("✅ Puzzle {} regenerated: {} words after {} failed attempts", args.number, words, failed);
+    save_edited_book(&book, args.output.as_deref().unwrap_or(&args.book))
+}
+
+fn move_puzzle(args: MoveArgs) -> Result<()> {
+    let mut book = load_book(&args.book)?;
+    let from = puzzle_index(&book, args.from)?;
+    let to = puzzle_index(&book, args.to)?;
+    book.move_puzzle(from, to).map_err(anyhow::Error::msg)?;
+
+    println!("✅ Moved puzzle {} to {}", args.from, args.to);
+    save_edited_book(&book, args.output.as_deref().unwrap_or(&args.book))
+}
+
+fn delete_puzzles(args: DeleteArgs) -> Result<()> {
+    let mut book = load_book(&args.book)?;
+    let mut indices = args.numbers.iter()
+        .map(|&number| puzzle_index(&book, number))
+        .collect::<Result<Vec<_>>>()?;
+    // From the back, so earlier deletions don't renumber later ones
+    indices.sort_unstable();
+    indices.dedup();
+    for &idx in indices.iter().rev() {
+        book.remove_puzzle(idx).map_err(anyhow::Error::msg)?;
+    }
+
+    println!("✅ Deleted {} puzzles", indices.len());
+    save_edited_book(&book, args.output.as_deref().unwrap_or(&args.book))
+}
+
+fn insert_puzzles(args: InsertArgs) -> Result<()> {
+    let mut book = load_book(&args.book)?;
+    let at = match args.at {
+        Some(0) => anyhow::bail!("Puzzle numbers start at 1"),
+        Some(number) => number - 1,
+        None => book.puzzle_count(),
+    };
+    let puzzles = import::load_puzzles(&args.file, || load_dictionary(&args.dictionary))?;
+
+    let count = puzzles.len();
+    for (offset, puzzle) in puzzles.into_iter().enumerate() {
+        book.insert_puzzle(at + offset, puzzle).map_err(anyhow::Error::msg)?;
+    }
+
+    println!("✅ Inserted {} puzzles from {} at puzzle {}", count, args.file.display(), at + 1);
+    save_edited_book(&book, args.output.as_deref().unwrap_or(&args.book))
+}
+
+/// 0-based index of a puzzle number given on the command line
+fn puzzle_index(book: &CrosswordBook, number: usize) -> Result<usize> {
+    if number == 0 || number > book.puzzle_count() {
+        anyhow::bail!("No puzzle {}: book has {} puzzles", number, book.puzzle_count());
+    }
+    Ok(number - 1)
+}
+
+fn save_edited_book(book: &CrosswordBook, output: &Path) -> Result<()> {
+    book.save_to_file(output)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    println!("Book {} now has {} puzzles. Re-render it to update the typeset output", output.display(), book.puzzle_count());
+    Ok(())
+}
+
+fn load_dictionary(args: &DictionaryArgs) -> Result<Dictionary> {
+    println!("Initializing dictionary...");
+    let allowlist = match args.allowlist {
+        Some(ref path) => {
+            let text = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read allowlist {}", path.display()))?;
+            println!("Using custom allowlist: {}", path.display());
+            Some(text)
+        }
+        None => None,
+    };
+
+    let mut dict = match args.dictionary {
+        Some(ref path) => {
+            let format = args.dictionary_format.as_deref()
+                .map(str::parse::<DictionaryFormat>)
+                .transpose()
+                .map_err(anyhow::Error::msg)?;
+            let (source, format) = crossword_core::load_word_source(path, format).map_err(anyhow::Error::msg)?;
+            println!("Using dictionary: {} ({})", path.display(), format.as_str());
+            // The default allowlist is for Oxford text; word lists are already curated
+            let allowlist = allowlist.as_deref()
+                .or((format == DictionaryFormat::Oxford).then_some(DEFAULT_ALLOWLIST));
+            Dictionary::from_source(source, allowlist)
+        }
+        None => embedded_dictionary(allowlist.as_deref())?,
+    };
+
+    if !args.exclude_labels.is_empty() {
+        let labels: Vec<&str> = args.exclude_labels.iter().map(|label| label.trim()).collect();
+        if !dict.has_labels() {
+            eprintln!("Warning: this dictionary has no usage labels, so --exclude-labels leaves every word in");
+        }
+        let dropped = dict.exclude_labels(&labels).map_err(anyhow::Error::msg)?;
+        println!("Excluded {} words labelled {}", dropped, labels.join(", "));
+    }
+    if let Some(ref path) = args.blocklist {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read blocklist {}", path.display()))?;
+        let dropped = dict.exclude_words(&text);
+        println!("Excluded {} words from blocklist {}", dropped, path.display());
+    }
+
+    let stats = dict.stats();
+    println!("Dictionary loaded: {} words (filtered)", stats.word_count);
+
+    if let Some(ref path) = args.clues {
+        let mut database = ClueDatabase::load(path).map_err(anyhow::Error::msg)?;
+        let difficulty = args.clue_difficulty.as_deref()
+            .map(str::parse::<Difficulty>)
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        database.set_difficulty(difficulty);
+        println!("Using clue database: {} ({} answers)", path.display(), database.len());
+        dict.set_clue_database(Some(database));
+    } else if args.clue_difficulty.is_some() {
+        anyhow::bail!("--clue-difficulty needs a clue database (--clues)");
+    }
+    Ok(dict)
+}
+
+#[cfg(feature = "embedded-dictionary")]
+fn embedded_dictionary(allowlist: Option<&str>) -> Result<Dictionary> {
+    if allowlist.is_none() {
+        println!("Using default allowlist");
+    }
+    Ok(Dictionary::with_allowlist(Some(allowlist.unwrap_or(DEFAULT_ALLOWLIST))))
+}
+
+#[cfg(not(feature = "embedded-dictionary"))]
+fn embedded_dictionary(_allowlist: Option<&str>) -> Result<Dictionary> {
+    anyhow::bail!("No dictionary given. Pass --dictionary <FILE> or set CROSSWORD_DICTIONARY, \
+                   or build with --features embedded-dictionary")
+}
+
+fn load_book(path: &Path) -> Result<CrosswordBook> {
+    CrosswordBook::load_from_file(path)
+        .with_context(|| format!("Failed to read book {}", path.display()))
+}
+
+fn write_latex(book: &CrosswordBook, stats: Option<&stats::BookStats>, output: &Path) -> Result<()> {
+    println!("Generating LaTeX document...");
+    let latex_gen = LatexGenerator::new();
+    let latex_content = latex_gen.generate_document(book, stats)
+        .context("Failed to generate LaTeX document")?;
+
+    fs::write(output, latex_content)
+        .context("Failed to write output file")?;
+
+    println!("✅ LaTeX: {}", output.display());
+    Ok(())
+}
+
+/// Write `pdf_path`, natively or by compiling a .tex written next to it
+fn compile(book: &CrosswordBook, stats: Option<&stats::BookStats>, pdf_path: &Path, engine: &str) -> Result<()> {
+    let result = match engine.to_lowercase().as_str() {
+        "native" => write_native_pdf(book, pdf_path),
+        "pdflatex" => {
+            let latex_path = pdf_path.with_extension("tex");
+            write_latex(book, stats, &latex_path)?;
+            compile_pdf(&latex_path)
+        }
+        other => Err(anyhow::anyhow!("Invalid PDF engine: {}. Use pdflatex or native", other)),
+    };
+    match result {
+        Ok(_) => {
+            println!("🎉 Done!");
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("\n⚠️  PDF failed: {}", e);
+            Err(e)
+        }
+    }
+}
+
+fn write_native_pdf(book: &CrosswordBook, pdf_path: &Path) -> Result<()> {
+    println!("Rendering PDF...");
+    let pdf = PdfGenerator::new().generate_document(book)
+        .map_err(anyhow::Error::msg)
+        .context("Failed to render PDF")?;
+
+    fs::write(pdf_path, pdf)
+        .context("Failed to write PDF")?;
+
+    println!("✅ PDF: {}", pdf_path.display());
+    Ok(())
+}
+
+/// Every puzzle with its clues, then its solution grid
+fn render_book_text(book: &CrosswordBook, style: TextStyle) -> String {
+    let options = TextOptions { style, ..Default::default() };
+    let solution_options = TextOptions { clues: false, ..options.clone() };
+
+    let mut text = String::new();
+    for chapter in book.chapters() {
+        if let Some(section) = chapter.section {
+            text.push_str(&format!("\n{}\n", section.title));
+            if let Some(summary) = chapter.summary() {
+                text.push_str(&format!("{}\n", summary));
             }
+            for paragraph in section.intro_paragraphs() {
+                text.push_str(&format!("\n{}\n", paragraph));
+            }
+        }
+        for (offset, puzzle) in chapter.puzzles.iter().enumerate() {
+            let number = chapter.first_number + offset;
+            text.push_str(&format!("\nPuzzle {}\n\n", number));
+            text.push_str(&render_puzzle_text(puzzle, &options));
+            text.push_str(&format!("\nSolution {}\n\n", number));
+            text.push_str(&render_solution_text(puzzle, &solution_options));
         }
     }
-    
-    words.truncate(max_words);
-    
-    // Solve the crossword
-    let (placements, elapsed_ms, _num_vars, _num_clauses) = solve_with_iterations(&words, size)
-        .map_err(|e| anyhow::anyhow!("Solver failed: {}", e))?;
-    
-    // Create puzzle
-    let puzzle = CrosswordPuzzle::from_placements(
-        &placements,
-        size,
-        |word| dict.get_clue(word),
-        elapsed_ms,
-    );
-    
-    Ok(puzzle)
+    text
 }
 
-fn compile_pdf(latex_path: &PathBuf) -> Result<()> {
+fn compile_pdf(latex_path: &Path) -> Result<()> {
     use std::process::Command;
     
-    // Check if pdflatex is installed
-    let check = Command::new("which")
-        .arg("pdflatex")
+    // Check if pdflatex is installed (running it works on every platform, unlike `which`)
+    let check = Command::new("pdflatex")
+        .arg("--version")
         .output();
     
-    if check.is_err() || !check.unwrap().status.success() {
+    if !check.is_ok_and(|output| output.status.success()) {
         eprintln!("\n❌ pdflatex not found");
-        eprintln!("\nInstall MacTeX:");
-        eprintln!("  brew install --cask mactex");
-        eprintln!("\nOr generate .tex only (remove --compile flag)");
+        eprintln!("\nUse the built-in renderer, which needs no TeX installation: --pdf-engine native");
+        eprintln!("Or install a TeX distribution with pdflatex (TeX Live, MiKTeX or MacTeX)");
+        eprintln!("Or write the .tex only: crossword-cli render <BOOK> -f latex");
         anyhow::bail!("pdflatex not installed");
     }
     
-    println!("Running pdflatex...");
-    let _output = Command::new("pdflatex")
-        .arg("-interaction=nonstopmode")
-        .arg(latex_path)
-        .output()
-        .context("Failed to run pdflatex")?;
+    // pdflatex writes into the current directory unless told otherwise
+    let output_dir = latex_path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
     
+    println!("Running pdflatex...");
     // Second pass for references
-    let _ = Command::new("pdflatex")
-        .arg("-interaction=nonstopmode")
-        .arg(latex_path)
-        .output();
+    for _ in 0..2 {
+        let status = Command::new("pdflatex")
+            .arg("-interaction=nonstopmode")
+            .arg("-output-directory")
+            .arg(output_dir)
+            .arg(latex_path)
+            .output()
+            .context("Failed to run pdflatex")?
+            .status;
+        
+        if !status.success() {
+            eprintln!("\n❌ pdflatex failed ({})", status);
+            eprintln!("\nMinimal TeX installs often lack packages the book uses; add them with your");
+            eprintln!("TeX distribution's package manager, or use --pdf-engine native");
+            eprintln!("\nSee: {}", latex_path.with_extension("log").display());
+            anyhow::bail!("Compilation failed");
+        }
+    }
     
-    // Check if PDF was actually created (even if pdflatex had warnings)
-    let pdf_path = latex_path.with_extension("pdf");
-    if pdf_path.exists() {
-        println!("✅ PDF: {}", pdf_path.display());
-        Ok(())
-    } else {
-        eprintln!("\n❌ pdflatex failed - no PDF created");
-        eprintln!("\nBasicTeX often has package issues. Install full MacTeX:");
-        eprintln!("  brew uninstall --cask basictex");
-        eprintln!("  brew install --cask mactex");
-        eprintln!("\nSee: {}", latex_path.with_extension("log").display());
-        anyhow::bail!("Compilation failed")
+    println!("✅ PDF: {}", latex_path.with_extension("pdf").display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::sample_puzzle;
+
+    const WORDS: &str = "CAT CAB TOT BET ACE ART ATE BAT BED BEE CAR COT DOG EAR EAT EGG ERA ODE OAT OAR \
+                         RAT RED ROT SEA SET TAR TEA TEE TEN TOE ARE ORE ONE NET NOT TAN ANT APE APT OPT";
+
+    /// Each puzzle's text in a saved book, exactly as written
+    fn puzzle_json(book: &str) -> Vec<String> {
+        let start = book.find("\"puzzles\": [\n").unwrap() + "\"puzzles\": [\n".len();
+        let end = start + book[start..].find("\n  ]").unwrap();
+        book[start..end].split("\n    },\n    {").map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_regenerate_keeps_other_puzzles() {
+        let dir = std::env::temp_dir().join(format!("crossword-regenerate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let words: String = WORDS.split_whitespace().map(|word| format!("{}\tClue for {}\n", word, word)).collect();
+        fs::write(dir.join("words.tsv"), words).unwrap();
+
+        let mut book = CrosswordBook::new(BookConfig::new("Test".to_string(), 5));
+        for number in 1..=3 {
+            book.add_puzzle(sample_puzzle(5, |word| format!("{} in puzzle {}", word, number)));
+        }
+        let path = dir.join("book.json");
+        book.save_to_file(&path).unwrap();
+        let before = puzzle_json(&fs::read_to_string(&path).unwrap());
+        assert_eq!(before.len(), 3);
+
+        regenerate_puzzle(RegenerateArgs {
+            book: path.clone(),
+            number: 2,
+            exclude: Vec::new(),
+            seed: Some(7),
+            dictionary: DictionaryArgs { dictionary: Some(dir.join("words.tsv")), ..Default::default() },
+            max_attempts: batch::DEFAULT_ATTEMPTS_PER_PUZZLE,
+            output: None,
+        }).unwrap();
+
+        let after = puzzle_json(&fs::read_to_string(&path).unwrap());
+        assert_eq!(after.len(), 3);
+        assert_eq!(after[0], before[0]);
+        assert_ne!(after[1], before[1]);
+        assert_eq!(after[2], before[2]);
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,290 @@
+use crossword_core::CrosswordPuzzle;
+use anyhow::{Result, Context};
+
+/// Settings for PNG grids. Physical sizes are in inches and scaled by `dpi`.
+#[derive(Debug, Clone)]
+pub struct PngOptions {
+    pub dpi: u32,
+    pub cell_size: f32,
+    pub padding: f32,
+    pub watermark: Option<String>,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self {
+            dpi: 150,
+            cell_size: 0.3,
+            padding: 0.1,
+            watermark: None,
+        }
+    }
+}
+
+impl PngOptions {
+    /// Reject settings that would make an unreasonably large (or empty) image
+    pub fn check(&self) -> Result<()> {
+        if !(1..=MAX_DPI).contains(&self.dpi) {
+            anyhow::bail!("Invalid PNG resolution: {} dpi. Use 1 to {}", self.dpi, MAX_DPI);
+        }
+        if !(0.05..=MAX_INCHES).contains(&self.cell_size) {
+            anyhow::bail!("Invalid PNG cell size: {} inches. Use 0.05 to {}", self.cell_size, MAX_INCHES);
+        }
+        if !(0.0..=MAX_INCHES).contains(&self.padding) {
+            anyhow::bail!("Invalid PNG padding: {} inches. Use 0 to {}", self.padding, MAX_INCHES);
+        }
+        Ok(())
+    }
+}
+
+/// Limits that keep a grid image under about 300 MB in memory
+const MAX_DPI: u32 = 1200;
+const MAX_INCHES: f32 = 2.0;
+const MAX_DIMENSION: usize = 10_000;
+
+const WHITE: [u8; 3] = [255, 255, 255];
+const BLACK: [u8; 3] = [0, 0, 0];
+const WATERMARK_GRAY: [u8; 3] = [128, 128, 128];
+const WATERMARK_ALPHA: f32 = 0.35;
+
+/// Rasterize a blank (numbered) or solution grid to PNG bytes, without any
+/// system graphics libraries or fonts
+pub fn render_png(puzzle: &CrosswordPuzzle, solution: bool, options: &PngOptions) -> Result<Vec<u8>> {
+    options.check()?;
+    let size = puzzle.grid.len();
+    let dpi = options.dpi as f32;
+    let cell = (options.cell_size * dpi).round().max(8.0) as usize;
+    let padding = (options.padding * dpi).round() as usize;
+    let line = (cell / 24).max(1);
+    let border = line * 2;
+    let dimension = size.checked_mul(cell)
+        .and_then(|grid| grid.checked_add(2 * padding + line))
+        .filter(|&dimension| dimension <= MAX_DIMENSION)
+        .with_context(|| format!(
+            "A {}x{} grid at {} dpi is over the {} pixel limit for PNGs. Lower --dpi",
+            size, size, options.dpi, MAX_DIMENSION
+        ))?;
+
+    let mut image = Image::new(dimension, dimension);
+    let numbers = puzzle.cell_numbers();
+
+    for (row, cells) in puzzle.grid.iter().enumerate() {
+        for (col, letter) in cells.iter().enumerate() {
+            let x = padding + col * cell;
+            let y = padding + row * cell;
+
+            match letter {
+                None => image.fill_rect(x, y, cell + line, cell + line, BLACK),
+                Some(letter) => {
+                    image.stroke_rect(x, y, cell + line, cell + line, line, BLACK);
+                    if solution {
+                        let scale = (cell * 11 / 20 / GLYPH_HEIGHT).max(1);
+                        let text = letter.to_string();
+                        let tx = x + (cell + line).saturating_sub(text_width(&text, scale)) / 2;
+                        let ty = y + (cell + line).saturating_sub(GLYPH_HEIGHT * scale) / 2;
+                        image.draw_text(tx, ty, &text, scale, BLACK, 1.0);
+                    } else if let Some(num) = numbers[row][col] {
+                        let scale = (cell / 4 / GLYPH_HEIGHT).max(1);
+                        image.draw_text(x + line + 1, y + line + 1, &num.to_string(), scale, BLACK, 1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    let grid_px = size * cell + line;
+    image.stroke_rect(padding, padding, grid_px, grid_px, border, BLACK);
+
+    if let Some(ref watermark) = options.watermark {
+        // Largest scale that fits in 80% of the grid width
+        let unit_width = text_width(watermark, 1).max(1);
+        let scale = (grid_px * 4 / 5 / unit_width).max(1);
+        let tx = padding + grid_px.saturating_sub(text_width(watermark, scale)) / 2;
+        let ty = padding + grid_px.saturating_sub(GLYPH_HEIGHT * scale) / 2;
+        image.draw_text(tx, ty, watermark, scale, WATERMARK_GRAY, WATERMARK_ALPHA);
+    }
+
+    image.encode_png(options.dpi)
+}
+
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: WHITE.repeat(width * height),
+        }
+    }
+
+    fn blend(&mut self, x: usize, y: usize, color: [u8; 3], alpha: f32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let idx = (y * self.width + x) * 3;
+        for (channel, &c) in color.iter().enumerate() {
+            let old = self.pixels[idx + channel] as f32;
+            self.pixels[idx + channel] = (old + (c as f32 - old) * alpha).round() as u8;
+        }
+    }
+
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, color: [u8; 3]) {
+        for py in y..y + h {
+            for px in x..x + w {
+                self.blend(px, py, color, 1.0);
+            }
+        }
+    }
+
+    fn stroke_rect(&mut self, x: usize, y: usize, w: usize, h: usize, line: usize, color: [u8; 3]) {
+        self.fill_rect(x, y, w, line, color);
+        self.fill_rect(x, (y + h).saturating_sub(line), w, line, color);
+        self.fill_rect(x, y, line, h, color);
+        self.fill_rect((x + w).saturating_sub(line), y, line, h, color);
+    }
+
+    fn draw_text(&mut self, x: usize, y: usize, text: &str, scale: usize, color: [u8; 3], alpha: f32) {
+        let mut cursor = x;
+        for c in text.chars() {
+            let rows = glyph(c);
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        for dy in 0..scale {
+                            for dx in 0..scale {
+                                self.blend(cursor + col * scale + dx, y + row * scale + dy, color, alpha);
+                            }
+                        }
+                    }
+                }
+            }
+            cursor += (GLYPH_WIDTH + 1) * scale;
+        }
+    }
+
+    fn encode_png(&self, dpi: u32) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let pixels_per_meter = (dpi as f32 / 0.0254).round() as u32;
+            encoder.set_pixel_dims(Some(png::PixelDimensions {
+                xppu: pixels_per_meter,
+                yppu: pixels_per_meter,
+                unit: png::Unit::Meter,
+            }));
+            let mut writer = encoder.write_header()
+                .context("Failed to write PNG header")?;
+            writer.write_image_data(&self.pixels)
+                .context("Failed to write PNG data")?;
+        }
+        Ok(out)
+    }
+}
+
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+
+fn text_width(text: &str, scale: usize) -> usize {
+    let count = text.chars().count();
+    if count == 0 {
+        0
+    } else {
+        (count * (GLYPH_WIDTH + 1) - 1) * scale
+    }
+}
+
+/// 5x7 bitmap glyphs, one byte per row with the leftmost pixel in bit 4
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        ' ' => [0; GLYPH_HEIGHT],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        '\'' => [0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        '/' => [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000],
+        '&' => [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        '©' => [0b01110, 0b10001, 0b10111, 0b10100, 0b10111, 0b10001, 0b01110],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100], // '?'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::sample_puzzle;
+
+    #[test]
+    fn test_render_png() {
+        let puzzle = sample_puzzle(3, |_| String::new());
+        let options = PngOptions {
+            dpi: 100,
+            watermark: Some("SAMPLE".to_string()),
+            ..Default::default()
+        };
+
+        let bytes = render_png(&puzzle, true, &options).unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+        let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        // 3 cells of 30px, 10px padding each side, plus the closing line
+        assert_eq!(info.width, 3 * 30 + 2 * 10 + 1);
+        assert_eq!(info.pixel_dims.unwrap().xppu, 3937);
+
+        let options = PngOptions { dpi: 100_000, ..Default::default() };
+        assert!(render_png(&puzzle, false, &options).unwrap_err().to_string().starts_with("Invalid PNG resolution"));
+        let options = PngOptions { cell_size: f32::NAN, ..Default::default() };
+        assert!(render_png(&puzzle, false, &options).is_err());
+        let big = sample_puzzle(60, |_| String::new());
+        let options = PngOptions { dpi: 1200, ..Default::default() };
+        assert!(render_png(&big, false, &options).unwrap_err().to_string().contains("pixel limit"));
+    }
+}
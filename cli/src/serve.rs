@@ -0,0 +1,320 @@
+use crate::batch;
+use crate::export;
+use crate::used_words::UsedWords;
+use crate::validate;
+use anyhow::{Context, Result};
+use crossword_core::{BookConfig, CrosswordPuzzle, Dictionary};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Largest grid a request may ask for or send, so one caller can't tie up a worker for hours
+pub const MAX_GRID_SIZE: usize = 21;
+
+/// Finished jobs kept for polling; older ones are dropped first
+const MAX_FINISHED_JOBS: usize = 1000;
+
+/// Request bodies beyond this are rejected
+const MAX_BODY_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Options for `POST /generate`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenerateRequest {
+    size: usize,
+    seed: Option<u64>,
+    /// Answers the puzzle must not use
+    #[serde(default)]
+    exclude: Vec<String>,
+    max_attempts: Option<usize>,
+}
+
+/// Body of `POST /validate` and `POST /export/{format}`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PuzzleRequest {
+    puzzle: CrosswordPuzzle,
+    /// Title used by ipuz, jpz and html exports
+    title: Option<String>,
+    /// Export the filled grid (svg, png and txt)
+    #[serde(default)]
+    solution: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done { puzzle: CrosswordPuzzle, failed_attempts: usize },
+    Failed { error: String },
+}
+
+impl JobStatus {
+    fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Done { .. } | JobStatus::Failed { .. })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    pub id: u64,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub status: JobStatus,
+}
+
+/// Generation jobs by id. At most `capacity` jobs are queued or running at
+/// once; further submissions are turned away rather than piling up.
+pub struct JobQueue {
+    capacity: usize,
+    next_id: Mutex<u64>,
+    jobs: Mutex<BTreeMap<u64, Job>>,
+}
+
+impl JobQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, next_id: Mutex::new(1), jobs: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Queue a job, or `None` when the queue is full
+    pub fn try_add(&self, seed: u64) -> Option<Job> {
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs.values().filter(|job| !job.status.is_finished()).count() >= self.capacity {
+            return None;
+        }
+
+        let mut next_id = self.next_id.lock().unwrap();
+        let job = Job { id: *next_id, seed, status: JobStatus::Queued };
+        *next_id += 1;
+        jobs.insert(job.id, job.clone());
+
+        // Ids only grow, so the first finished jobs are the oldest
+        let finished: Vec<u64> = jobs.values().filter(|job| job.status.is_finished()).map(|job| job.id).collect();
+        for id in finished.iter().take(finished.len().saturating_sub(MAX_FINISHED_JOBS)) {
+            jobs.remove(id);
+        }
+        Some(job)
+    }
+
+    pub fn set_status(&self, id: u64, status: JobStatus) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(&id) {
+            job.status = status;
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<Job> {
+        self.jobs.lock().unwrap().get(&id).cloned()
+    }
+
+    /// Jobs queued or running
+    pub fn active(&self) -> usize {
+        self.jobs.lock().unwrap().values().filter(|job| !job.status.is_finished()).count()
+    }
+}
+
+/// Everything the request handlers share
+pub struct Service {
+    pub dict: Dictionary,
+    pub queue: JobQueue,
+    pub pool: rayon::ThreadPool,
+}
+
+/// A response before it goes on the wire
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        let body = serde_json::to_vec_pretty(value).unwrap_or_default();
+        Self { status, content_type: "application/json", body }
+    }
+
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message.to_string() }))
+    }
+}
+
+/// Answer requests on `addr` until the process is stopped
+pub fn run(service: Service, addr: &str) -> Result<()> {
+    let server = Server::http(addr)
+        .map_err(|e| anyhow::anyhow!("Failed to listen on {}: {}", addr, e))?;
+    let service = Arc::new(service);
+
+    println!("✅ Listening on http://{}", addr);
+    println!("Endpoints: GET /health, GET /dictionary, POST /generate, GET /jobs/<id>, POST /validate, POST /export/<format>");
+    for request in server.incoming_requests() {
+        respond(&service, request);
+    }
+    Ok(())
+}
+
+fn respond(service: &Arc<Service>, mut request: Request) {
+    let mut body = Vec::new();
+    let reply = match request.as_reader().take(MAX_BODY_BYTES + 1).read_to_end(&mut body) {
+        Err(e) => Reply::error(400, format!("Failed to read request body: {}", e)),
+        Ok(_) if body.len() as u64 > MAX_BODY_BYTES => Reply::error(413, "Request body too large"),
+        Ok(_) => route(service, request.method(), request.url(), &body),
+    };
+    println!("{} {} -> {}", request.method(), request.url(), reply.status);
+
+    let header = Header::from_bytes("Content-Type", reply.content_type).expect("valid header");
+    let response = Response::from_data(reply.body)
+        .with_status_code(reply.status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        eprintln!("Failed to send response: {}", e);
+    }
+}
+
+fn route(service: &Arc<Service>, method: &Method, url: &str, body: &[u8]) -> Reply {
+    let path = url.split('?').next().unwrap_or_default().trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["health"]) => Reply::json(200, &serde_json::json!({
+            "status": "ok",
+            "active_jobs": service.queue.active(),
+        })),
+        (Method::Get, ["dictionary"]) => dictionary_stats(&service.dict),
+        (Method::Post, ["generate"]) => submit_generate(service, body),
+        (Method::Get, ["jobs", id]) => match id.parse().ok().and_then(|id| service.queue.get(id)) {
+            Some(job) => Reply::json(200, &job),
+            None => Reply::error(404, format!("No job {}", id)),
+        },
+        (Method::Post, ["validate"]) => match parse_puzzle(body) {
+            Ok(request) => Reply::json(200, &validate::validate_single_puzzle(&request.puzzle)),
+            Err(reply) => reply,
+        },
+        (Method::Post, ["export", format]) => export_puzzle(format, body),
+        (_, ["health" | "dictionary" | "generate" | "validate"] | ["jobs" | "export", _]) => {
+            Reply::error(405, format!("{} is not allowed on {}", method, path))
+        }
+        _ => Reply::error(404, format!("No endpoint {}", path)),
+    }
+}
+
+fn dictionary_stats(dict: &Dictionary) -> Reply {
+    let mut by_length = BTreeMap::new();
+    for word in dict.get_words() {
+        *by_length.entry(word.chars().count()).or_insert(0usize) += 1;
+    }
+    Reply::json(200, &serde_json::json!({
+        "stats": dict.stats(),
+        "words_by_length": by_length,
+    }))
+}
+
+fn submit_generate(service: &Arc<Service>, body: &[u8]) -> Reply {
+    let request: GenerateRequest = match parse_body(body) {
+        Ok(request) => request,
+        Err(reply) => return reply,
+    };
+    if !(3..=MAX_GRID_SIZE).contains(&request.size) {
+        return Reply::error(400, format!("size must be between 3 and {}", MAX_GRID_SIZE));
+    }
+    let max_attempts = request.max_attempts.unwrap_or(batch::DEFAULT_ATTEMPTS_PER_PUZZLE);
+    if !(1..=100).contains(&max_attempts) {
+        return Reply::error(400, "max_attempts must be between 1 and 100");
+    }
+
+    let seed = request.seed.unwrap_or_else(rand::random);
+    let Some(job) = service.queue.try_add(seed) else {
+        return Reply::error(503, "Job queue is full, try again later");
+    };
+
+    let id = job.id;
+    let worker = Arc::clone(service);
+    service.pool.spawn(move || {
+        worker.queue.set_status(id, JobStatus::Running);
+        let mut used = UsedWords::new(None);
+        for word in &request.exclude {
+            used.exclude(word.trim());
+        }
+        let status = match batch::generate_with_retries(&worker.dict, request.size, seed, max_attempts, Some(&used)) {
+            Ok((puzzle, failed_attempts)) => JobStatus::Done { puzzle, failed_attempts },
+            Err(e) => JobStatus::Failed { error: format!("{:#}", e) },
+        };
+        worker.queue.set_status(id, status);
+    });
+    Reply::json(202, &job)
+}
+
+fn export_puzzle(format: &str, body: &[u8]) -> Reply {
+    let request = match parse_puzzle(body) {
+        Ok(request) => request,
+        Err(reply) => return reply,
+    };
+    // Exporters index the grid by clue position, so only well-formed puzzles get that far
+    let report = validate::validate_single_puzzle(&request.puzzle);
+    if !report.is_ok() {
+        return Reply::json(422, &report);
+    }
+
+    let size = request.puzzle.grid.len();
+    let config = BookConfig::new(request.title.unwrap_or_else(|| "Crossword".to_string()), size);
+
+    match export::export_puzzle(&request.puzzle, &config, 1, format, request.solution) {
+        Ok((body, content_type)) => Reply { status: 200, content_type, body },
+        Err(e) if e.to_string().starts_with("Invalid export format") => Reply::error(404, e),
+        Err(e) => Reply::error(422, format!("{:#}", e)),
+    }
+}
+
+/// A `PuzzleRequest`, turned away before any work when its grid is over the size limit
+fn parse_puzzle(body: &[u8]) -> Result<PuzzleRequest, Reply> {
+    let request: PuzzleRequest = parse_body(body)?;
+    let size = request.puzzle.grid.len();
+    if size > MAX_GRID_SIZE {
+        return Err(Reply::error(400, format!("Puzzle is {}x{}; grids can be at most {}x{}", size, size, MAX_GRID_SIZE, MAX_GRID_SIZE)));
+    }
+    Ok(request)
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Result<T, Reply> {
+    serde_json::from_slice(body)
+        .context("Invalid request JSON")
+        .map_err(|e| Reply::error(400, format!("{:#}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::sample_puzzle;
+
+    #[test]
+    fn test_job_queue() {
+        let queue = JobQueue::new(2);
+        let first = queue.try_add(7).unwrap();
+        let second = queue.try_add(8).unwrap();
+        assert!(queue.try_add(9).is_none());
+        assert_eq!((first.id, second.id), (1, 2));
+
+        queue.set_status(first.id, JobStatus::Failed { error: "UNSAT".to_string() });
+        assert_eq!(queue.active(), 1);
+        let third = queue.try_add(9).unwrap();
+        assert_eq!(third.id, 3);
+
+        let json = serde_json::to_value(queue.get(first.id).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!({ "id": 1, "seed": 7, "status": "failed", "error": "UNSAT" }));
+        assert!(queue.get(4).is_none());
+    }
+
+    #[test]
+    fn test_grid_size_limit() {
+        let body = |size| serde_json::to_vec(&serde_json::json!({
+            "puzzle": sample_puzzle(size, |word| format!("Clue for {}", word)),
+        })).unwrap();
+
+        assert!(parse_puzzle(&body(MAX_GRID_SIZE)).is_ok());
+        let Err(reply) = parse_puzzle(&body(MAX_GRID_SIZE + 1)) else { panic!("oversized grid accepted") };
+        assert_eq!(reply.status, 400);
+        assert_eq!(export_puzzle("svg", &body(MAX_GRID_SIZE + 1)).status, 400);
+        assert_eq!(export_puzzle("svg", &body(5)).status, 200);
+    }
+}
@@ -0,0 +1,281 @@
+use crossword_core::{CrosswordBook, CrosswordPuzzle, Direction, MISSING_CLUE};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// How many entries the "most repeated" and "lowest crossing" lists keep
+const TOP_N: usize = 10;
+
+/// Density histogram bucket width
+const DENSITY_STEP: f32 = 0.1;
+
+/// Quality report for a whole book, built from its puzzles and their
+/// generation metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct BookStats {
+    pub puzzles: usize,
+    pub words: usize,
+    /// Puzzles per density band, from the lowest band used to the highest
+    pub density: Vec<DensityBucket>,
+    /// Number of answers of each length
+    pub word_lengths: BTreeMap<usize, usize>,
+    pub repeated_answers: Vec<Repeat>,
+    pub repeated_clues: Vec<Repeat>,
+    /// Puzzles with the smallest share of letters that are crossed, lowest first
+    pub lowest_crossing: Vec<PuzzleCrossing>,
+    /// None when no puzzle records a generation time, e.g. an imported book
+    pub generation_time_ms: Option<Percentiles>,
+    /// Clues that fell back to the dictionary's placeholder
+    pub missing_clues: Vec<MissingClue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DensityBucket {
+    pub from: f32,
+    pub to: f32,
+    pub puzzles: usize,
+}
+
+/// An answer or clue text and how many times the book uses it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Repeat {
+    pub text: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PuzzleCrossing {
+    /// 1-based, as printed in the book
+    pub puzzle: usize,
+    /// Letters in both an across and a down entry, over all letters
+    pub ratio: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Percentiles {
+    pub p50: u32,
+    pub p90: u32,
+    pub p99: u32,
+    pub max: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingClue {
+    pub puzzle: usize,
+    pub number: usize,
+    pub direction: &'static str,
+    pub answer: String,
+}
+
+impl BookStats {
+    pub fn new(book: &CrosswordBook) -> Self {
+        let puzzles = book.puzzles();
+
+        let mut word_lengths = BTreeMap::new();
+        let mut answers: HashMap<&str, usize> = HashMap::new();
+        let mut clues: HashMap<&str, usize> = HashMap::new();
+        let mut missing_clues = Vec::new();
+        for (idx, puzzle) in puzzles.iter().enumerate() {
+            for (direction, entries) in [(Direction::Across, &puzzle.across_clues), (Direction::Down, &puzzle.down_clues)] {
+                for clue in entries {
+                    *word_lengths.entry(clue.word.chars().count()).or_default() += 1;
+                    *answers.entry(clue.word.as_str()).or_default() += 1;
+                    if clue.clue == MISSING_CLUE {
+                        missing_clues.push(MissingClue {
+                            puzzle: idx + 1,
+                            number: clue.number,
+                            direction: direction.as_str(),
+                            answer: clue.word.clone(),
+                        });
+                    } else {
+                        *clues.entry(clue.clue.as_str()).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let mut lowest_crossing: Vec<PuzzleCrossing> = puzzles.iter()
+            .enumerate()
+            .map(|(idx, puzzle)| PuzzleCrossing { puzzle: idx + 1, ratio: crossing_ratio(puzzle) })
+            .collect();
+        lowest_crossing.sort_by(|a, b| a.ratio.total_cmp(&b.ratio).then(a.puzzle.cmp(&b.puzzle)));
+        lowest_crossing.truncate(TOP_N);
+
+        Self {
+            puzzles: puzzles.len(),
+            words: word_lengths.values().sum(),
+            density: density_histogram(puzzles),
+            word_lengths,
+            repeated_answers: most_repeated(answers),
+            repeated_clues: most_repeated(clues),
+            lowest_crossing,
+            generation_time_ms: percentiles(puzzles),
+            missing_clues,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("Puzzles: {}\nAnswers: {}\n", self.puzzles, self.words);
+
+        text.push_str("\nDensity\n");
+        let widest = self.density.iter().map(|b| b.puzzles).max().unwrap_or(0).max(1);
+        for bucket in &self.density {
+            let bar = "#".repeat((bucket.puzzles * 40).div_ceil(widest));
+            text.push_str(&format!("  {:.0}-{:.0}%  {:>5}  {}\n", bucket.from * 100.0, bucket.to * 100.0, bucket.puzzles, bar));
+        }
+
+        text.push_str("\nWord lengths\n");
+        for (length, count) in &self.word_lengths {
+            text.push_str(&format!("  {:>2} letters  {:>6}\n", length, count));
+        }
+
+        text.push_str("\nMost repeated answers\n");
+        push_repeats(&mut text, &self.repeated_answers);
+        text.push_str("\nMost repeated clues\n");
+        push_repeats(&mut text, &self.repeated_clues);
+
+        text.push_str("\nLowest crossing ratio\n");
+        for crossing in &self.lowest_crossing {
+            text.push_str(&format!("  Puzzle {:<5} {:.0}% of letters crossed\n", crossing.puzzle, crossing.ratio * 100.0));
+        }
+
+        text.push_str("\nGeneration time\n");
+        match &self.generation_time_ms {
+            Some(t) => text.push_str(&format!(
+                "  p50 {}  p90 {}  p99 {}  max {}\n",
+                format_ms(t.p50), format_ms(t.p90), format_ms(t.p99), format_ms(t.max)
+            )),
+            None => text.push_str("  not recorded\n"),
+        }
+
+        text.push_str(&format!("\nClues without a definition: {}\n", self.missing_clues.len()));
+        for missing in &self.missing_clues {
+            text.push_str(&format!("  Puzzle {}: {} {} ({})\n", missing.puzzle, missing.number, missing.direction, missing.answer));
+        }
+        text
+    }
+}
+
+/// Share of letter cells that belong to both an across and a down entry
+fn crossing_ratio(puzzle: &CrosswordPuzzle) -> f32 {
+    let grid = &puzzle.grid;
+    let letter = |row: usize, col: Option<usize>| -> bool {
+        col.and_then(|col| grid.get(row).and_then(|r| r.get(col)))
+            .is_some_and(|cell| cell.is_some())
+    };
+
+    let (mut letters, mut crossed) = (0, 0);
+    for (row, cells) in grid.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            if cell.is_none() {
+                continue;
+            }
+            letters += 1;
+            let across = letter(row, col.checked_sub(1)) || letter(row, Some(col + 1));
+            let down = row.checked_sub(1).is_some_and(|up| letter(up, Some(col))) || letter(row + 1, Some(col));
+            if across && down {
+                crossed += 1;
+            }
+        }
+    }
+    if letters == 0 { 0.0 } else { crossed as f32 / letters as f32 }
+}
+
+fn density_histogram(puzzles: &[CrosswordPuzzle]) -> Vec<DensityBucket> {
+    let buckets = (1.0 / DENSITY_STEP).round() as usize;
+    let mut counts = vec![0; buckets];
+    for puzzle in puzzles {
+        let bucket = (puzzle.metadata.density / DENSITY_STEP) as usize;
+        counts[bucket.min(buckets - 1)] += 1;
+    }
+
+    let (Some(first), Some(last)) = (counts.iter().position(|&n| n > 0), counts.iter().rposition(|&n| n > 0)) else {
+        return Vec::new();
+    };
+    (first..=last)
+        .map(|bucket| DensityBucket {
+            from: bucket as f32 * DENSITY_STEP,
+            to: (bucket + 1) as f32 * DENSITY_STEP,
+            puzzles: counts[bucket],
+        })
+        .collect()
+}
+
+/// Texts used more than once, most used first
+fn most_repeated(counts: HashMap<&str, usize>) -> Vec<Repeat> {
+    let mut repeats: Vec<Repeat> = counts.into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(text, count)| Repeat { text: text.to_string(), count })
+        .collect();
+    repeats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.text.cmp(&b.text)));
+    repeats.truncate(TOP_N);
+    repeats
+}
+
+/// Nearest-rank percentiles of the recorded generation times. Imported
+/// puzzles record 0 and are left out.
+fn percentiles(puzzles: &[CrosswordPuzzle]) -> Option<Percentiles> {
+    let mut times: Vec<u32> = puzzles.iter()
+        .map(|p| p.metadata.generation_time_ms)
+        .filter(|&ms| ms > 0)
+        .collect();
+    if times.is_empty() {
+        return None;
+    }
+    times.sort_unstable();
+    let rank = |p: usize| times[((p * times.len()).div_ceil(100)).max(1) - 1];
+    Some(Percentiles {
+        p50: rank(50),
+        p90: rank(90),
+        p99: rank(99),
+        max: times[times.len() - 1],
+    })
+}
+
+fn push_repeats(text: &mut String, repeats: &[Repeat]) {
+    if repeats.is_empty() {
+        text.push_str("  none\n");
+    }
+    for repeat in repeats {
+        text.push_str(&format!("  {:>4}x  {}\n", repeat.count, repeat.text));
+    }
+}
+
+fn format_ms(ms: u32) -> String {
+    if ms >= 1000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::BookConfig;
+    use crossword_core::test_util::sample_puzzle;
+
+    #[test]
+    fn test_book_stats() {
+        let clue = |word: &str| if word == "CAT" { "Pet".to_string() } else { MISSING_CLUE.to_string() };
+
+        let mut book = CrosswordBook::new(BookConfig::new("Test".to_string(), 3));
+        for ms in [100, 300, 200] {
+            let mut puzzle = sample_puzzle(3, clue);
+            puzzle.metadata.generation_time_ms = ms;
+            book.add_puzzle(puzzle);
+        }
+        let stats = BookStats::new(&book);
+
+        assert_eq!((stats.puzzles, stats.words), (3, 6));
+        assert_eq!(stats.word_lengths, BTreeMap::from([(3, 6)]));
+        assert_eq!(stats.repeated_answers[0], Repeat { text: "CAB".to_string(), count: 3 });
+        assert_eq!(stats.repeated_clues, vec![Repeat { text: "Pet".to_string(), count: 3 }]);
+        assert_eq!(stats.missing_clues.len(), 3);
+        assert_eq!(stats.missing_clues[0].direction, "Down");
+        // Only the shared C is crossed
+        assert_eq!(stats.lowest_crossing[0], PuzzleCrossing { puzzle: 1, ratio: 0.2 });
+        assert_eq!(stats.generation_time_ms, Some(Percentiles { p50: 200, p90: 300, p99: 300, max: 300 }));
+        assert_eq!(stats.density.iter().map(|b| b.puzzles).sum::<usize>(), 3);
+        assert!(stats.to_text().contains("Clues without a definition: 3"));
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use crossword_core::CrosswordPuzzle;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// How often each answer has been used, in this book and in earlier
+/// volumes. Used answers go to the back of later puzzles' word pools;
+/// answers at `max_repeats` are left out entirely.
+#[derive(Debug, Clone, Default)]
+pub struct UsedWords {
+    counts: HashMap<String, usize>,
+    max_repeats: Option<usize>,
+    /// Never sampled, whatever their count
+    excluded: HashSet<String>,
+}
+
+impl UsedWords {
+    pub fn new(max_repeats: Option<usize>) -> Self {
+        Self { counts: HashMap::new(), max_repeats, excluded: HashSet::new() }
+    }
+
+    /// Read a registry written by `save`. A missing file is an empty registry.
+    pub fn load(path: &Path, max_repeats: Option<usize>) -> Result<Self> {
+        let mut used = Self::new(max_repeats);
+        if path.exists() {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read used words {}", path.display()))?;
+            used.parse(&text)
+                .with_context(|| format!("Invalid used words file {}", path.display()))?;
+        }
+        Ok(used)
+    }
+
+    /// One `WORD<TAB>COUNT` line per answer, sorted. A line with just a
+    /// word counts once, so a plain word list works as a registry too.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_text())
+            .with_context(|| format!("Failed to write used words {}", path.display()))
+    }
+
+    pub fn add(&mut self, word: &str, uses: usize) {
+        *self.counts.entry(word.to_uppercase()).or_default() += uses;
+    }
+
+    pub fn add_puzzle(&mut self, puzzle: &CrosswordPuzzle) {
+        for clue in puzzle.across_clues.iter().chain(&puzzle.down_clues) {
+            self.add(&clue.word, 1);
+        }
+    }
+
+    /// Record a puzzle's answers unless one of them is excluded or already
+    /// at the repeat limit. Parallel workers sample from snapshots, so a
+    /// finished puzzle has to be checked again before it counts.
+    pub fn try_add_puzzle(&mut self, puzzle: &CrosswordPuzzle) -> Result<(), String> {
+        for clue in puzzle.across_clues.iter().chain(&puzzle.down_clues) {
+            let word = clue.word.to_uppercase();
+            if self.excluded.contains(&word) {
+                return Err(format!("Repeated answer {}: it is excluded", word));
+            }
+            if let Some(max) = self.max_repeats.filter(|&max| self.count(&word) >= max) {
+                return Err(format!("Repeated answer {}: already used {} times, --max-repeats is {}", word, self.count(&word), max));
+            }
+        }
+        self.add_puzzle(puzzle);
+        Ok(())
+    }
+
+    pub fn exclude(&mut self, word: &str) {
+        self.excluded.insert(word.to_uppercase());
+    }
+
+    pub fn count(&self, word: &str) -> usize {
+        self.counts.get(word).copied().unwrap_or(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Drop excluded words and words at the repeat limit, and move used ones behind unused
+    /// ones, keeping the existing (shuffled) order otherwise
+    pub fn prefer_unused(&self, words: &mut Vec<String>) {
+        if let Some(max) = self.max_repeats {
+            words.retain(|word| self.count(word) < max);
+        }
+        words.retain(|word| !self.excluded.contains(word));
+        words.sort_by_key(|word| self.count(word));
+    }
+
+    fn parse(&mut self, text: &str) -> Result<()> {
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let word = fields.next().unwrap_or_default();
+            let uses = match fields.next() {
+                Some(count) => count.parse()
+                    .with_context(|| format!("Line {}: invalid count '{}'", idx + 1, count))?,
+                None => 1,
+            };
+            self.add(word, uses);
+        }
+        Ok(())
+    }
+
+    fn to_text(&self) -> String {
+        let mut words: Vec<(&String, &usize)> = self.counts.iter().collect();
+        words.sort();
+        words.iter().map(|(word, uses)| format!("{}\t{}\n", word, uses)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::test_util::sample_puzzle;
+
+    #[test]
+    fn test_used_words() {
+        let mut used = UsedWords::new(Some(2));
+        used.parse("# Volume 1\nCAT\t2\ndog\nEMU 1\n").unwrap();
+        assert_eq!(used.count("DOG"), 1);
+        assert_eq!(used.to_text(), "CAT\t2\nDOG\t1\nEMU\t1\n");
+
+        let mut words: Vec<String> = ["DOG", "CAT", "OWL", "EMU", "ANT"].iter().map(|w| w.to_string()).collect();
+        used.prefer_unused(&mut words);
+        assert_eq!(words, ["OWL", "ANT", "DOG", "EMU"]);
+
+        used.exclude("owl");
+        used.prefer_unused(&mut words);
+        assert_eq!(words, ["ANT", "DOG", "EMU"]);
+
+        let placements = vec![crossword_core::Placement { word: "DOG".to_string(), x: 0, y: 0, horizontal: true }];
+        let puzzle = CrosswordPuzzle::from_placements(&placements, 3, |_| "Pet".to_string(), 0);
+        assert!(used.try_add_puzzle(&puzzle).is_ok());
+        assert_eq!(used.try_add_puzzle(&puzzle).unwrap_err(), "Repeated answer DOG: already used 2 times, --max-repeats is 2");
+        assert_eq!(used.count("DOG"), 2);
+
+        // A puzzle counts only when none of its answers is over the limit
+        let puzzle = sample_puzzle(3, |_| "Pet".to_string());
+        assert_eq!(used.try_add_puzzle(&puzzle).unwrap_err(), "Repeated answer CAT: already used 2 times, --max-repeats is 2");
+        assert_eq!(used.count("CAB"), 0);
+        let mut fresh = UsedWords::new(Some(1));
+        assert!(fresh.try_add_puzzle(&puzzle).is_ok());
+        assert_eq!(fresh.count("CAB"), 1);
+        assert!(fresh.try_add_puzzle(&puzzle).is_err());
+
+        assert!(used.parse("CAT many").unwrap_err().to_string().contains("Line 1: invalid count 'many'"));
+    }
+}
@@ -0,0 +1,157 @@
+use crossword_core::{Clue, CrosswordBook, CrosswordPuzzle, MISSING_CLUE};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Problems found in a book. Errors make the book unfit to typeset;
+/// warnings are editorial issues worth a look.
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Check every puzzle's grid, numbering and clues
+pub fn validate_book(book: &CrosswordBook) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    if book.puzzle_count() == 0 {
+        report.errors.push("Book has no puzzles".to_string());
+    }
+
+    for (idx, puzzle) in book.puzzles().iter().enumerate() {
+        let label = format!("Puzzle {}", idx + 1);
+        let size = puzzle.grid.len();
+        let (expected, setting) = match book.section_of(idx) {
+            Some(section) => (section.grid_size, format!("section \"{}\" is", section.title)),
+            None => (book.config().grid_size, "the book is".to_string()),
+        };
+        if size != expected {
+            report.warnings.push(format!(
+                "{}: grid is {}x{} but {} set up for {}x{}",
+                label, size, size, setting, expected, expected
+            ));
+        }
+        validate_puzzle(puzzle, &label, &mut report);
+    }
+
+    report
+}
+
+/// Check one puzzle on its own, outside any book
+pub fn validate_single_puzzle(puzzle: &CrosswordPuzzle) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    validate_puzzle(puzzle, "Puzzle", &mut report);
+    report
+}
+
+fn validate_puzzle(puzzle: &CrosswordPuzzle, label: &str, report: &mut ValidationReport) {
+    let size = puzzle.grid.len();
+    if size == 0 {
+        report.errors.push(format!("{}: grid is empty", label));
+        return;
+    }
+    if let Some(row) = puzzle.grid.iter().position(|row| row.len() != size) {
+        report.errors.push(format!("{}: row {} has {} cells, expected {}", label, row + 1, puzzle.grid[row].len(), size));
+        return;
+    }
+
+    // The numbering and answers the grid itself implies
+    let placements = CrosswordPuzzle::placements_from_grid(&puzzle.grid);
+    let expected = CrosswordPuzzle::from_placements(&placements, size, |_| String::new(), 0);
+
+    for (direction, actual, expected) in [
+        ("Across", &puzzle.across_clues, &expected.across_clues),
+        ("Down", &puzzle.down_clues, &expected.down_clues),
+    ] {
+        compare_entries(label, direction, actual, expected, report);
+
+        for clue in actual {
+            let text = clue.clue.trim();
+            if text.is_empty() {
+                report.errors.push(format!("{}: {} {} ({}) has no clue", label, clue.number, direction, clue.word));
+            } else if text == MISSING_CLUE {
+                report.warnings.push(format!("{}: {} {} ({}) has no definition", label, clue.number, direction, clue.word));
+            } else if contains_word(text, &clue.word) {
+                report.warnings.push(format!("{}: {} {} clue contains its answer {}", label, clue.number, direction, clue.word));
+            }
+        }
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for clue in puzzle.across_clues.iter().chain(&puzzle.down_clues) {
+        *seen.entry(clue.word.as_str()).or_default() += 1;
+    }
+    let mut repeated: Vec<&str> = seen.into_iter().filter(|(_, n)| *n > 1).map(|(w, _)| w).collect();
+    repeated.sort();
+    for word in repeated {
+        report.warnings.push(format!("{}: answer {} appears more than once", label, word));
+    }
+}
+
+/// Clues must cover exactly the grid's entries, with matching numbers and answers
+fn compare_entries(label: &str, direction: &str, actual: &[Clue], expected: &[Clue], report: &mut ValidationReport) {
+    let by_cell: HashMap<(usize, usize), &Clue> = actual.iter().map(|c| ((c.x, c.y), c)).collect();
+
+    for entry in expected {
+        match by_cell.get(&(entry.x, entry.y)) {
+            None => report.errors.push(format!(
+                "{}: grid entry {} {} ({}) has no clue", label, entry.number, direction, entry.word
+            )),
+            Some(clue) if clue.word != entry.word => report.errors.push(format!(
+                "{}: {} {} answer {} does not match the grid ({})", label, clue.number, direction, clue.word, entry.word
+            )),
+            Some(clue) if clue.number != entry.number => report.errors.push(format!(
+                "{}: {} {} ({}) should be numbered {}", label, clue.number, direction, clue.word, entry.number
+            )),
+            Some(_) => {}
+        }
+    }
+
+    for clue in actual {
+        if !expected.iter().any(|e| e.x == clue.x && e.y == clue.y) {
+            report.errors.push(format!(
+                "{}: {} {} ({}) is not an entry in the grid", label, clue.number, direction, clue.word
+            ));
+        }
+    }
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric())
+        .any(|token| token.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossword_core::BookConfig;
+    use crossword_core::test_util::sample_puzzle;
+
+    #[test]
+    fn test_validate_book() {
+        let clue = |word: &str| if word == "CAT" { "A cat".to_string() } else { "Taxi".to_string() };
+        let good = sample_puzzle(3, clue);
+
+        let mut bad = good.clone();
+        bad.grid[0][2] = Some('B'); // CAT no longer matches the grid
+        bad.down_clues[0].clue = String::new();
+
+        let mut book = CrosswordBook::new(BookConfig::new("Test".to_string(), 3));
+        book.add_puzzle(good);
+        book.add_puzzle(bad);
+
+        let report = validate_book(&book);
+        assert_eq!(report.errors, vec![
+            "Puzzle 2: 1 Across answer CAT does not match the grid (CAB)".to_string(),
+            "Puzzle 2: 1 Down (CAB) has no clue".to_string(),
+        ]);
+        assert!(report.warnings.contains(&"Puzzle 1: 1 Across clue contains its answer CAT".to_string()));
+        assert!(!report.is_ok());
+    }
+}
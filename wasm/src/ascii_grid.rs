@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use crate::solution::CrosswordPuzzle;
+
+struct ClueLine {
+    number: usize,
+    text: String,
+    line: usize,
+}
+
+/// Parse a hand-drawn grid: rows of letters with `#` or `.` for blocks,
+/// optionally followed by `ACROSS:` and `DOWN:` sections of `N. clue` lines.
+/// Entries without a clue are filled in with `clue_fn(word)`.
+pub fn parse_ascii_grid(text: &str, clue_fn: impl Fn(&str) -> String) -> Result<CrosswordPuzzle, String> {
+    let mut grid: Vec<Vec<Option<char>>> = Vec::new();
+    let mut across: Vec<ClueLine> = Vec::new();
+    let mut down: Vec<ClueLine> = Vec::new();
+    let mut section: Option<bool> = None; // Some(true) = across, Some(false) = down
+    let mut grid_done = false;
+
+    for (idx, raw) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = raw.trim();
+
+        if let Some(horizontal) = section_header(line) {
+            section = Some(horizontal);
+            grid_done = true;
+            continue;
+        }
+        if line.is_empty() {
+            grid_done = grid_done || !grid.is_empty();
+            continue;
+        }
+
+        match section {
+            Some(horizontal) => {
+                let clue = parse_clue_line(line, line_no)?;
+                if horizontal { across.push(clue) } else { down.push(clue) }
+            }
+            None if grid_done => {
+                return Err(format!("Line {}: expected ACROSS: or DOWN: after the grid", line_no));
+            }
+            None => grid.push(parse_grid_row(line, line_no)?),
+        }
+    }
+
+    if grid.is_empty() {
+        return Err("No grid found".to_string());
+    }
+    let size = grid.len();
+    if let Some(row) = grid.iter().position(|row| row.len() != size) {
+        return Err(format!(
+            "Only square grids are supported (row {} has {} cells, grid has {} rows)",
+            row + 1, grid[row].len(), size
+        ));
+    }
+
+    let placements = CrosswordPuzzle::placements_from_grid(&grid);
+    if placements.is_empty() {
+        return Err("Grid has no entries".to_string());
+    }
+
+    let mut puzzle = CrosswordPuzzle::from_placements(&placements, size, |_| String::new(), 0);
+    let across_map = clue_map(&across, "across", &puzzle.across_clues.iter().map(|c| c.number).collect::<Vec<_>>())?;
+    let down_map = clue_map(&down, "down", &puzzle.down_clues.iter().map(|c| c.number).collect::<Vec<_>>())?;
+
+    for clue in &mut puzzle.across_clues {
+        clue.clue = across_map.get(&clue.number).cloned().unwrap_or_else(|| clue_fn(&clue.word));
+    }
+    for clue in &mut puzzle.down_clues {
+        clue.clue = down_map.get(&clue.number).cloned().unwrap_or_else(|| clue_fn(&clue.word));
+    }
+
+    Ok(puzzle)
+}
+
+fn section_header(line: &str) -> Option<bool> {
+    match line.trim_end_matches(':').trim().to_ascii_uppercase().as_str() {
+        "ACROSS" => Some(true),
+        "DOWN" => Some(false),
+        _ => None,
+    }
+}
+
+/// Letters are entries, `#` and `.` are blocks; spaces between cells are ignored
+fn parse_grid_row(line: &str, line_no: usize) -> Result<Vec<Option<char>>, String> {
+    line.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '#' | '.' => Ok(None),
+            c if c.is_alphabetic() => Ok(Some(c.to_uppercase().next().unwrap_or(c))),
+            c => Err(format!("Line {}: unexpected character '{}' in grid", line_no, c)),
+        })
+        .collect()
+}
+
+/// Accepts "12. Clue", "12: Clue", "12) Clue" and "12 Clue"
+fn parse_clue_line(line: &str, line_no: usize) -> Result<ClueLine, String> {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    let number = line[..digits].parse::<usize>()
+        .map_err(|_| format!("Line {}: expected a numbered clue, got '{}'", line_no, line))?;
+
+    let rest = line[digits..].trim_start();
+    let rest = rest.strip_prefix(['.', ':', ')']).unwrap_or(rest);
+    let text = strip_enumeration(rest.trim());
+    if text.is_empty() {
+        return Err(format!("Line {}: clue {} has no text", line_no, number));
+    }
+
+    Ok(ClueLine { number, text: text.to_string(), line: line_no })
+}
+
+/// Drop a trailing "(5)" or "(3,4)"; renderers add their own enumeration
+fn strip_enumeration(text: &str) -> &str {
+    if let Some(open) = text.rfind('(') {
+        let inner = &text[open..];
+        if inner.ends_with(')')
+            && inner.len() > 2
+            && inner[1..inner.len() - 1].chars().all(|c| c.is_ascii_digit() || c == ',' || c == '-')
+        {
+            return text[..open].trim_end();
+        }
+    }
+    text
+}
+
+fn clue_map(clues: &[ClueLine], direction: &str, numbers: &[usize]) -> Result<HashMap<usize, String>, String> {
+    let mut map = HashMap::new();
+    for clue in clues {
+        if !numbers.contains(&clue.number) {
+            return Err(format!("Line {}: grid has no {} entry numbered {}", clue.line, direction, clue.number));
+        }
+        if map.insert(clue.number, clue.text.clone()).is_some() {
+            return Err(format!("Line {}: duplicate {} clue {}", clue.line, direction, clue.number));
+        }
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ascii_grid() {
+        let text = "c a t\nA#O\nB.E\n\nACROSS:\n1. Feline (3)\n\nDOWN:\n1) Taxi\n2 Foot digit\n";
+        let puzzle = parse_ascii_grid(text, |word| format!("Missing {}", word)).unwrap();
+
+        assert_eq!(puzzle.grid[0], vec![Some('C'), Some('A'), Some('T')]);
+        assert_eq!(puzzle.grid[1][1], None);
+        assert_eq!(puzzle.grid[2][1], None);
+        assert_eq!(puzzle.across_clues.len(), 1);
+        assert_eq!(puzzle.across_clues[0].clue, "Feline");
+        assert_eq!(puzzle.down_clues[0].clue, "Taxi");
+        assert_eq!(puzzle.down_clues[1].word, "TOE");
+        assert_eq!(puzzle.down_clues[1].clue, "Foot digit");
+
+        let puzzle = parse_ascii_grid("CAT\nA#O\nBET\n", |word| format!("Missing {}", word)).unwrap();
+        assert_eq!(puzzle.across_clues[1].number, 3);
+        assert_eq!(puzzle.across_clues[1].clue, "Missing BET");
+    }
+
+    #[test]
+    fn test_parse_ascii_grid_errors() {
+        assert!(parse_ascii_grid("CAT\nA#\nBET\n", |_| String::new())
+            .unwrap_err().contains("square"));
+        assert!(parse_ascii_grid("CAT\nA?O\nBET\n", |_| String::new())
+            .unwrap_err().starts_with("Line 2"));
+        assert!(parse_ascii_grid("CAT\nA#O\nBET\nDOWN:\n9. Nope\n", |_| String::new())
+            .unwrap_err().contains("no down entry numbered 9"));
+    }
+}
@@ -0,0 +1,545 @@
+use crate::solution::CrosswordPuzzle;
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookConfig {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub author: Option<String>,
+    pub publisher: Option<String>,
+    pub edition: Option<String>,
+    pub isbn: Option<String>,
+    pub copyright_year: Option<String>,
+    pub description: Option<String>,
+    pub grid_size: usize,
+    pub puzzles_per_page: usize,
+    pub kdp_format: KdpFormat,
+    pub trim_size: TrimSize,
+    /// Chapters in book order; empty for a book that is one run of puzzles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<Section>,
+}
+
+/// The book's introduction, one entry per paragraph. Each renderer sets
+/// the first letter as a drop cap (see `drop_cap`).
+pub const INTRODUCTION: [&str; 4] = [
+    "Crossword puzzles have captivated minds for over a century, beginning with Arthur Wynne's Word-Cross puzzle published in the New York World on December 21, 1913. What started as a simple diamond-shaped grid has evolved into one of the world's most beloved pastimes, challenging millions of solvers daily.",
+    "The beauty of a well-crafted crossword lies in the delicate balance between challenge and satisfaction. Each puzzle is a carefully constructed lattice of interlocking words, where every letter serves double duty, connecting both across and down entries. The best puzzles reward both knowledge and wordplay, offering that satisfying \u{201C}aha!\u{201D} moment when a difficult clue finally clicks.",
+    "This collection is designed to provide hours of engaging entertainment. Whether you're a seasoned cruciverbalist or a curious beginner, these puzzles offer a perfect blend of vocabulary, general knowledge, and lateral thinking.",
+    "Each puzzle is printed with the grid on the right page and clues on the left, allowing you to see both simultaneously as you solve. Take your time, work in pencil, and remember: every puzzle has a solution, and the journey to finding it is half the fun.",
+];
+
+/// Works the introduction names, set in italics where the format allows
+pub const INTRODUCTION_TITLES: [&str; 2] = ["Word-Cross", "New York World"];
+
+/// Split a paragraph into its first letter, for a drop cap, and the rest
+pub fn drop_cap(paragraph: &str) -> (&str, &str) {
+    paragraph.split_at(paragraph.chars().next().map_or(0, char::len_utf8))
+}
+
+/// A chapter of the book with its own heading, intro and grid size
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Section {
+    pub title: String,
+    pub intro: Option<String>,
+    pub grid_size: usize,
+}
+
+impl Section {
+    /// The intro split into paragraphs at blank lines
+    pub fn intro_paragraphs(&self) -> Vec<&str> {
+        self.intro.iter()
+            .flat_map(|intro| intro.split("\n\n"))
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum KdpFormat {
+    Paperback,
+    Ebook,
+}
+
+impl KdpFormat {
+    pub fn from_string(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "paperback" => Ok(KdpFormat::Paperback),
+            "ebook" => Ok(KdpFormat::Ebook),
+            _ => Err(format!("Invalid KDP format: {}. Use paperback or ebook", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TrimSize {
+    pub width: f32,  // inches
+    pub height: f32, // inches
+}
+
+impl TrimSize {
+    pub fn from_string(s: &str) -> Result<Self, String> {
+        match s {
+            "5x8" => Ok(TrimSize { width: 5.0, height: 8.0 }),
+            "5.5x8.5" => Ok(TrimSize { width: 5.5, height: 8.5 }),
+            "6x9" => Ok(TrimSize { width: 6.0, height: 9.0 }),
+            "7x10" => Ok(TrimSize { width: 7.0, height: 10.0 }),
+            "8x10" => Ok(TrimSize { width: 8.0, height: 10.0 }),
+            _ => Err(format!("Invalid trim size: {}. Use 5x8, 5.5x8.5, 6x9, 7x10, or 8x10", s)),
+        }
+    }
+}
+
+/// Page margins in inches
+#[derive(Debug, Clone, Copy)]
+pub struct Margins {
+    pub top: f32,
+    pub bottom: f32,
+    pub inner: f32,
+    pub outer: f32,
+}
+
+impl BookConfig {
+    pub fn new(title: String, grid_size: usize) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            author: None,
+            publisher: None,
+            edition: None,
+            isbn: None,
+            copyright_year: None,
+            description: None,
+            grid_size,
+            puzzles_per_page: 1,
+            kdp_format: KdpFormat::Paperback,
+            trim_size: TrimSize { width: 8.0, height: 10.0 },
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn margins(&self) -> Margins {
+        match self.kdp_format {
+            KdpFormat::Paperback => {
+                // No bleed for text-only puzzle books
+                // Margins based on KDP requirements (assuming 100-400 pages)
+                Margins {
+                    top: 0.75,
+                    bottom: 0.75,
+                    inner: 0.625,  // Gutter for binding
+                    outer: 0.5,
+                }
+            }
+            KdpFormat::Ebook => {
+                // Ebook: simpler margins
+                Margins {
+                    top: 0.5,
+                    bottom: 0.5,
+                    inner: 0.5,
+                    outer: 0.5,
+                }
+            }
+        }
+    }
+}
+
+/// Which clue list an entry belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Across,
+    Down,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Across => "Across",
+            Direction::Down => "Down",
+        }
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "across" | "a" => Ok(Direction::Across),
+            "down" | "d" => Ok(Direction::Down),
+            other => Err(format!("Invalid direction: {}. Use across or down", other)),
+        }
+    }
+}
+
+/// One clue of a book as shown to editors: puzzle (1-based), number,
+/// direction and answer identify the entry, `clue` is the editable text
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClueRecord {
+    pub puzzle: usize,
+    pub number: usize,
+    pub direction: Direction,
+    pub answer: String,
+    pub clue: String,
+}
+
+/// A run of consecutive puzzles from one section
+#[derive(Debug)]
+pub struct Chapter<'a> {
+    /// `None` for books without sections
+    pub section: Option<&'a Section>,
+    /// Book-wide number of the first puzzle (1-based)
+    pub first_number: usize,
+    pub puzzles: &'a [CrosswordPuzzle],
+}
+
+impl Chapter<'_> {
+    /// Book-wide number of the last puzzle (1-based)
+    pub fn last_number(&self) -> usize {
+        self.first_number + self.puzzles.len() - 1
+    }
+
+    /// "Puzzles 11–20 · 15×15 grids", shown under a section's heading
+    pub fn summary(&self) -> Option<String> {
+        let section = self.section?;
+        let numbers = if self.puzzles.len() == 1 {
+            format!("Puzzle {}", self.first_number)
+        } else {
+            format!("Puzzles {}–{}", self.first_number, self.last_number())
+        };
+        Some(format!("{} · {}×{} grids", numbers, section.grid_size, section.grid_size))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrosswordBook {
+    config: BookConfig,
+    puzzles: Vec<CrosswordPuzzle>,
+    /// Index into `config.sections` for each puzzle, when the book has sections
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    puzzle_sections: Vec<usize>,
+}
+
+impl CrosswordBook {
+    pub fn new(config: BookConfig) -> Self {
+        Self {
+            config,
+            puzzles: Vec::new(),
+            puzzle_sections: Vec::new(),
+        }
+    }
+
+    /// Append a puzzle to the last section
+    pub fn add_puzzle(&mut self, puzzle: CrosswordPuzzle) {
+        let last = self.config.sections.len().saturating_sub(1);
+        self.add_section_puzzle(last, puzzle);
+    }
+
+    /// Append a puzzle to `section` (an index into `config().sections`).
+    /// Puzzles are numbered in the order they are added, so add them
+    /// section by section. The index is ignored for books without sections.
+    pub fn add_section_puzzle(&mut self, section: usize, puzzle: CrosswordPuzzle) {
+        if !self.config.sections.is_empty() {
+            self.puzzle_sections.push(section.min(self.config.sections.len() - 1));
+        }
+        self.puzzles.push(puzzle);
+    }
+
+    pub fn puzzles(&self) -> &[CrosswordPuzzle] {
+        &self.puzzles
+    }
+
+    pub fn puzzle_count(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    pub fn config(&self) -> &BookConfig {
+        &self.config
+    }
+
+    /// The section puzzle `idx` (0-based) belongs to
+    pub fn section_of(&self, idx: usize) -> Option<&Section> {
+        self.puzzle_sections.get(idx).and_then(|&section| self.config.sections.get(section))
+    }
+
+    /// Puzzles grouped into chapters, in book order. A book without
+    /// sections is a single chapter.
+    pub fn chapters(&self) -> Vec<Chapter<'_>> {
+        let mut chapters = Vec::new();
+        let mut start = 0;
+        while start < self.puzzles.len() {
+            let len = match self.puzzle_sections.get(start) {
+                Some(section) => self.puzzle_sections[start..].iter().take_while(|s| *s == section).count(),
+                None => self.puzzles.len() - start,
+            };
+            chapters.push(Chapter {
+                section: self.section_of(start),
+                first_number: start + 1,
+                puzzles: &self.puzzles[start..start + len],
+            });
+            start += len;
+        }
+        chapters
+    }
+
+    /// Replace puzzle `idx` (0-based), keeping its section. Returns the old puzzle.
+    pub fn replace_puzzle(&mut self, idx: usize, puzzle: CrosswordPuzzle) -> Result<CrosswordPuzzle, String> {
+        self.check_index(idx)?;
+        Ok(std::mem::replace(&mut self.puzzles[idx], puzzle))
+    }
+
+    /// Remove puzzle `idx` (0-based); later puzzles move up one number
+    pub fn remove_puzzle(&mut self, idx: usize) -> Result<CrosswordPuzzle, String> {
+        self.check_index(idx)?;
+        if !self.puzzle_sections.is_empty() {
+            self.puzzle_sections.remove(idx);
+        }
+        Ok(self.puzzles.remove(idx))
+    }
+
+    /// Insert a puzzle so it becomes puzzle `idx` (0-based; the puzzle count
+    /// appends). It joins the section of the puzzle before it, or of the one
+    /// after it when inserted at the start, and must match its grid size.
+    pub fn insert_puzzle(&mut self, idx: usize, puzzle: CrosswordPuzzle) -> Result<(), String> {
+        if idx > self.puzzles.len() {
+            return Err(format!("Cannot insert at {}: book has {} puzzles", idx + 1, self.puzzles.len()));
+        }
+        let section = self.section_for(idx, None);
+        self.check_grid_size(section, &puzzle)?;
+        self.insert_at(idx, puzzle, section);
+        Ok(())
+    }
+
+    /// Move puzzle `from` so it becomes puzzle `to` (both 0-based). It stays
+    /// in its section when it lands next to a puzzle of that section, joins
+    /// its new neighbours' section if the grid size matches, and otherwise
+    /// keeps its own section.
+    pub fn move_puzzle(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.check_index(from)?;
+        self.check_index(to)?;
+        let section = self.puzzle_sections.get(from).copied();
+        let puzzle = self.remove_puzzle(from)?;
+        let mut target = self.section_for(to, section);
+        if let Err(error) = self.check_grid_size(target, &puzzle) {
+            if section.is_none() || self.check_grid_size(section, &puzzle).is_err() {
+                self.insert_at(from, puzzle, section);
+                return Err(error);
+            }
+            target = section;
+        }
+        self.insert_at(to, puzzle, target);
+        Ok(())
+    }
+
+    /// The section a puzzle inserted at `idx` joins: `preferred` when a
+    /// neighbour is in it, otherwise the neighbours' section
+    fn section_for(&self, idx: usize, preferred: Option<usize>) -> Option<usize> {
+        if self.config.sections.is_empty() {
+            return None;
+        }
+        let before = idx.checked_sub(1).and_then(|i| self.puzzle_sections.get(i)).copied();
+        let after = self.puzzle_sections.get(idx).copied();
+        Some(match preferred {
+            Some(section) if before == Some(section) || after == Some(section) => section,
+            _ => before.or(after).unwrap_or(self.config.sections.len() - 1),
+        })
+    }
+
+    fn check_grid_size(&self, section: Option<usize>, puzzle: &CrosswordPuzzle) -> Result<(), String> {
+        let size = puzzle.grid.len();
+        match section.and_then(|section| self.config.sections.get(section)) {
+            Some(section) if section.grid_size != size => Err(format!(
+                "Puzzle is {}x{} but section \"{}\" has {}x{} grids",
+                size, size, section.title, section.grid_size, section.grid_size
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn insert_at(&mut self, idx: usize, puzzle: CrosswordPuzzle, section: Option<usize>) {
+        if let Some(section) = section {
+            self.puzzle_sections.insert(idx, section);
+        }
+        self.puzzles.insert(idx, puzzle);
+    }
+
+    fn check_index(&self, idx: usize) -> Result<(), String> {
+        if idx >= self.puzzles.len() {
+            return Err(format!("No puzzle {}: book has {} puzzles", idx + 1, self.puzzles.len()));
+        }
+        Ok(())
+    }
+
+    /// Every clue in the book, puzzle by puzzle, across before down
+    pub fn clue_records(&self) -> Vec<ClueRecord> {
+        let mut records = Vec::new();
+        for (idx, puzzle) in self.puzzles.iter().enumerate() {
+            for (direction, clues) in [(Direction::Across, &puzzle.across_clues), (Direction::Down, &puzzle.down_clues)] {
+                for clue in clues {
+                    records.push(ClueRecord {
+                        puzzle: idx + 1,
+                        number: clue.number,
+                        direction,
+                        answer: clue.word.clone(),
+                        clue: clue.clue.clone(),
+                    });
+                }
+            }
+        }
+        records
+    }
+
+    /// Replace clue texts from edited records. Each record must still name an
+    /// existing entry with the same answer; if any record fails, nothing is
+    /// changed and every problem is reported. Returns how many clues changed.
+    pub fn merge_clues(&mut self, records: &[ClueRecord]) -> Result<usize, String> {
+        let mut problems = Vec::new();
+        let mut updates = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for record in records {
+            let label = format!("Puzzle {} {} {}", record.puzzle, record.number, record.direction.as_str());
+            if !seen.insert((record.puzzle, record.number, record.direction)) {
+                problems.push(format!("{}: listed more than once", label));
+                continue;
+            }
+            let Some(puzzle) = record.puzzle.checked_sub(1).and_then(|idx| self.puzzles.get(idx)) else {
+                problems.push(format!("{}: book has {} puzzles", label, self.puzzles.len()));
+                continue;
+            };
+            let clues = match record.direction {
+                Direction::Across => &puzzle.across_clues,
+                Direction::Down => &puzzle.down_clues,
+            };
+            let Some(pos) = clues.iter().position(|c| c.number == record.number) else {
+                problems.push(format!("{}: no such entry", label));
+                continue;
+            };
+            if normalize_answer(&record.answer) != normalize_answer(&clues[pos].word) {
+                problems.push(format!("{}: answer {} does not match {}", label, record.answer.trim(), clues[pos].word));
+                continue;
+            }
+            let text = record.clue.trim();
+            if text.is_empty() {
+                problems.push(format!("{}: clue is empty", label));
+                continue;
+            }
+            updates.push((record.puzzle - 1, record.direction, pos, text.to_string()));
+        }
+
+        if !problems.is_empty() {
+            return Err(format!("{} clue(s) could not be merged:\n  {}", problems.len(), problems.join("\n  ")));
+        }
+
+        let mut changed = 0;
+        for (idx, direction, pos, text) in updates {
+            let puzzle = &mut self.puzzles[idx];
+            let clue = match direction {
+                Direction::Across => &mut puzzle.across_clues[pos],
+                Direction::Down => &mut puzzle.down_clues[pos],
+            };
+            if clue.clue != text {
+                clue.clue = text;
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+
+    pub fn save_to_file(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn load_from_file(path: &std::path::Path) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let book = serde_json::from_str(&json)?;
+        Ok(book)
+    }
+}
+
+/// Answers compare by letters only, ignoring case, spaces and punctuation
+fn normalize_answer(answer: &str) -> String {
+    answer.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_uppercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sample_puzzle;
+
+    fn puzzle(size: usize) -> CrosswordPuzzle {
+        sample_puzzle(size, |_| "Pet".to_string())
+    }
+
+    #[test]
+    fn test_chapters() {
+        let mut book = CrosswordBook::new(BookConfig::new("Test".to_string(), 3));
+        book.add_puzzle(puzzle(3));
+        book.add_puzzle(puzzle(3));
+        let chapters = book.chapters();
+        assert_eq!(chapters.len(), 1);
+        assert!(chapters[0].section.is_none());
+        assert_eq!(chapters[0].puzzles.len(), 2);
+        assert!(chapters[0].summary().is_none());
+
+        let mut config = BookConfig::new("Test".to_string(), 3);
+        config.sections = vec![
+            Section { title: "Warm-up".to_string(), intro: None, grid_size: 3 },
+            Section { title: "Expert".to_string(), intro: Some("Harder".to_string()), grid_size: 5 },
+        ];
+        let mut book = CrosswordBook::new(config);
+        book.add_section_puzzle(0, puzzle(3));
+        book.add_section_puzzle(1, puzzle(5));
+        book.add_section_puzzle(1, puzzle(5));
+
+        let chapters = book.chapters();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].section.unwrap().title, "Expert");
+        assert_eq!(chapters[1].first_number, 2);
+        assert_eq!(chapters[1].puzzles.len(), 2);
+        assert_eq!(chapters[0].summary().unwrap(), "Puzzle 1 · 3×3 grids");
+        assert_eq!(chapters[1].summary().unwrap(), "Puzzles 2–3 · 5×5 grids");
+        assert_eq!(chapters[1].section.unwrap().intro_paragraphs(), ["Harder"]);
+        assert_eq!(book.section_of(0).unwrap().grid_size, 3);
+
+        // A moved puzzle keeps its section next to puzzles of that section,
+        // joins its new neighbours' section when the grid size matches, and
+        // otherwise keeps its own section
+        let titles = |book: &CrosswordBook| -> Vec<String> {
+            (0..book.puzzle_count()).map(|idx| book.section_of(idx).unwrap().title.clone()).collect()
+        };
+        book.move_puzzle(2, 1).unwrap();
+        assert_eq!(titles(&book), ["Warm-up", "Expert", "Expert"]);
+        book.move_puzzle(0, 0).unwrap();
+        assert_eq!(titles(&book), ["Warm-up", "Expert", "Expert"]);
+        book.move_puzzle(1, 0).unwrap();
+        assert_eq!(titles(&book), ["Expert", "Warm-up", "Expert"]);
+        book.move_puzzle(0, 1).unwrap();
+        assert_eq!(titles(&book), ["Warm-up", "Expert", "Expert"]);
+        book.move_puzzle(0, 2).unwrap();
+        assert_eq!(titles(&book), ["Expert", "Expert", "Warm-up"]);
+        book.move_puzzle(2, 0).unwrap();
+        assert_eq!(titles(&book), ["Warm-up", "Expert", "Expert"]);
+        assert_eq!(book.puzzles()[0].grid.len(), 3);
+        let error = book.insert_puzzle(3, puzzle(3)).unwrap_err();
+        assert_eq!(error, "Puzzle is 3x3 but section \"Expert\" has 5x5 grids");
+
+        let mut config = book.config().clone();
+        config.sections.push(Section { title: "Final".to_string(), intro: None, grid_size: 5 });
+        book.config = config;
+        book.add_section_puzzle(2, puzzle(5));
+        book.move_puzzle(3, 2).unwrap();
+        assert_eq!(titles(&book), ["Warm-up", "Expert", "Expert", "Expert"]);
+        assert!(book.insert_puzzle(4, puzzle(3)).is_err());
+        book.remove_puzzle(3).unwrap();
+
+        book.insert_puzzle(0, puzzle(3)).unwrap();
+        book.remove_puzzle(3).unwrap();
+        assert_eq!(book.puzzle_count(), 3);
+        assert!(book.remove_puzzle(3).is_err());
+    }
+}
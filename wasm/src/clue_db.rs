@@ -0,0 +1,225 @@
+use crate::word_source::normalize_word;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(format!("Invalid difficulty: {}. Use easy, medium or hard", other)),
+        }
+    }
+}
+
+/// One hand-written clue for an answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClueEntry {
+    pub answer: String,
+    pub clue: String,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    /// Where the clue came from, e.g. an editor or an earlier volume
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Editor-maintained clues, checked before the dictionary's definitions.
+/// An answer may have several clues; one matching the preferred difficulty
+/// is used first.
+#[derive(Debug, Clone, Default)]
+pub struct ClueDatabase {
+    clues: HashMap<String, Vec<ClueEntry>>,
+    difficulty: Option<Difficulty>,
+}
+
+impl ClueDatabase {
+    /// `answer<TAB>clue[<TAB>difficulty[<TAB>source]]` lines, as saved from a
+    /// spreadsheet. A first line starting with "answer" is a header.
+    pub fn parse_tsv(text: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#')
+                || (idx == 0 && line.to_lowercase().starts_with("answer")) {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            if fields.len() < 2 || fields[1].is_empty() {
+                return Err(format!("Line {}: expected answer<TAB>clue", idx + 1));
+            }
+            let difficulty = match fields.get(2) {
+                Some(value) if !value.is_empty() => Some(value.parse().map_err(|e| format!("Line {}: {}", idx + 1, e))?),
+                _ => None,
+            };
+            entries.push(ClueEntry {
+                answer: fields[0].to_string(),
+                clue: fields[1].to_string(),
+                difficulty,
+                source: fields.get(3).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+            });
+        }
+        Self::from_entries(entries)
+    }
+
+    /// A JSON array of `{"answer", "clue", "difficulty", "source"}` objects
+    pub fn parse_json(text: &str) -> Result<Self, String> {
+        let entries: Vec<ClueEntry> = serde_json::from_str(text)
+            .map_err(|e| format!("Invalid clue JSON: {}", e))?;
+        Self::from_entries(entries)
+    }
+
+    /// Read a `.json` or TSV clue file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read clues {}: {}", path.display(), e))?;
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let parsed = if is_json { Self::parse_json(&text) } else { Self::parse_tsv(&text) };
+        parsed.map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn from_entries(entries: Vec<ClueEntry>) -> Result<Self, String> {
+        let mut clues: HashMap<String, Vec<ClueEntry>> = HashMap::new();
+        for mut entry in entries {
+            let answer = normalize_word(&entry.answer)
+                .ok_or_else(|| format!("Answer {:?} must be 3-15 letters", entry.answer))?;
+            entry.answer = answer.clone();
+            clues.entry(answer).or_default().push(entry);
+        }
+        Ok(Self { clues, difficulty: None })
+    }
+
+    /// Prefer clues of this difficulty; others are used when an answer has none
+    pub fn set_difficulty(&mut self, difficulty: Option<Difficulty>) {
+        self.difficulty = difficulty;
+    }
+
+    /// Every clue for an uppercase answer, in file order
+    pub fn entries(&self, answer: &str) -> &[ClueEntry] {
+        self.clues.get(answer).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Clues for an answer, those of the preferred difficulty first
+    pub fn ranked(&self, answer: &str) -> Vec<&ClueEntry> {
+        let mut entries: Vec<&ClueEntry> = self.entries(answer).iter().collect();
+        if self.difficulty.is_some() {
+            // Stable, so file order holds within each group
+            entries.sort_by_key(|entry| entry.difficulty != self.difficulty);
+        }
+        entries
+    }
+
+    pub fn clue(&self, answer: &str) -> Option<&ClueEntry> {
+        self.ranked(answer).first().copied()
+    }
+
+    /// Number of answers with at least one clue
+    pub fn len(&self) -> usize {
+        self.clues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clues.is_empty()
+    }
+}
+
+/// Spreads clues across a book: each time an answer comes up it gets a
+/// clue the book hasn't given it yet, for as long as there is one
+#[derive(Debug, Clone, Default)]
+pub struct ClueRotation {
+    used: HashMap<String, HashMap<String, usize>>,
+}
+
+impl ClueRotation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note a clue the book already gives an answer
+    pub fn record(&mut self, answer: &str, clue: &str) {
+        *self.used.entry(answer.to_uppercase()).or_default().entry(clue.to_string()).or_default() += 1;
+    }
+
+    pub fn times_used(&self, answer: &str, clue: &str) -> usize {
+        self.used.get(&answer.to_uppercase()).and_then(|clues| clues.get(clue)).copied().unwrap_or(0)
+    }
+
+    /// The least used of `candidates`. Ties go to the earliest candidate,
+    /// or with `shortest` to the shortest one.
+    pub fn pick<'c>(&self, answer: &str, candidates: &'c [String], shortest: bool) -> Option<&'c String> {
+        candidates.iter()
+            .enumerate()
+            .min_by_key(|(idx, clue)| {
+                let tie = if shortest { clue.chars().count() } else { *idx };
+                (self.times_used(answer, clue), tie, *idx)
+            })
+            .map(|(_, clue)| clue)
+    }
+
+    /// Pick a clue and record it
+    pub fn take(&mut self, answer: &str, candidates: &[String]) -> Option<String> {
+        let clue = self.pick(answer, candidates, false)?.clone();
+        self.record(answer, &clue);
+        Some(clue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clue_database() {
+        let tsv = "answer\tclue\tdifficulty\tsource\n\
+                   cat\tPurring pet\teasy\tvol1\n\
+                   CAT\tIt may have nine lives\thard\n\
+                   ice cream\tSundae base\n";
+        let mut db = ClueDatabase::parse_tsv(tsv).unwrap();
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.entries("CAT").len(), 2);
+        assert_eq!(db.clue("CAT").unwrap().source.as_deref(), Some("vol1"));
+        assert_eq!(db.clue("ICECREAM").unwrap().clue, "Sundae base");
+
+        db.set_difficulty(Some(Difficulty::Hard));
+        assert_eq!(db.clue("CAT").unwrap().clue, "It may have nine lives");
+        assert_eq!(db.clue("ICECREAM").unwrap().clue, "Sundae base");
+        assert!(db.clue("DOG").is_none());
+
+        let json = r#"[{"answer": "owl", "clue": "Night hunter", "difficulty": "medium"}]"#;
+        let db = ClueDatabase::parse_json(json).unwrap();
+        assert_eq!(db.clue("OWL").unwrap().difficulty, Some(Difficulty::Medium));
+
+        assert_eq!(ClueDatabase::parse_tsv("CAT\tPet\tvery hard").unwrap_err(),
+                   "Line 1: Invalid difficulty: very hard. Use easy, medium or hard");
+        assert!(ClueDatabase::parse_tsv("OX\tYoke wearer").is_err());
+    }
+
+    #[test]
+    fn test_clue_rotation() {
+        let senses = vec!["A small domesticated feline".to_string(), "Jazz fan".to_string(), "Pet".to_string()];
+        let mut rotation = ClueRotation::new();
+        assert_eq!(rotation.take("cat", &senses).as_deref(), Some("A small domesticated feline"));
+        assert_eq!(rotation.take("CAT", &senses).as_deref(), Some("Jazz fan"));
+        assert_eq!(rotation.pick("CAT", &senses, true).map(String::as_str), Some("Pet"));
+
+        rotation.record("CAT", "Pet");
+        // Every clue used once: back to the first, or the shortest
+        assert_eq!(rotation.pick("CAT", &senses, false).map(String::as_str), Some("A small domesticated feline"));
+        assert_eq!(rotation.pick("CAT", &senses, true).map(String::as_str), Some("Pet"));
+        assert_eq!(rotation.times_used("cat", "Jazz fan"), 1);
+        assert!(rotation.take("DOG", &[]).is_none());
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use crate::clue_db::ClueDatabase;
+use crate::word_source::{canonical_label, known_labels, normalize_word, WordSource, MISSING_CLUE};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,27 +11,140 @@ pub struct DictionaryStats {
     pub max_word_length: usize,
 }
 
+/// Answer words and clues from a `WordSource`, narrowed by an optional
+/// allowlist and by blocklists and usage labels. Clues come from the clue
+/// database first, when one is set.
 pub struct Dictionary {
-    entries: HashMap<String, String>,
+    source: Box<dyn WordSource>,
     words: Vec<String>,
+    clue_database: Option<ClueDatabase>,
 }
 
 impl Dictionary {
+    /// The Oxford dictionary compiled into the binary
+    #[cfg(feature = "embedded-dictionary")]
     pub fn new() -> Self {
         Self::with_allowlist(None)
     }
     
+    /// The embedded Oxford dictionary, keeping only allowlisted words
+    #[cfg(feature = "embedded-dictionary")]
     pub fn with_allowlist(allowlist: Option<&str>) -> Self {
         let dict_text = include_str!("../Oxford_English_Dictionary.txt");
-        let mut entries = HashMap::new();
+        Self::from_source(Box::new(OxfordSource::parse(dict_text)), allowlist)
+    }
+    
+    /// Use any word source. With an allowlist (one word per line), only
+    /// listed words are offered as answers.
+    pub fn from_source(source: Box<dyn WordSource>, allowlist: Option<&str>) -> Self {
+        let allowed_words = allowlist.map(word_set);
+        
+        let mut words = source.words();
+        if let Some(ref allowed) = allowed_words {
+            words.retain(|word| allowed.contains(word));
+        }
+        
+        Dictionary { source, words, clue_database: None }
+    }
+    
+    pub fn get_words(&self) -> &[String] {
+        &self.words
+    }
+    
+    /// Drop answers listed in a blocklist (one word per line). Returns how
+    /// many were dropped.
+    pub fn exclude_words(&mut self, blocklist: &str) -> usize {
+        let blocked = word_set(blocklist);
+        let before = self.words.len();
+        self.words.retain(|word| !blocked.contains(word));
+        before - self.words.len()
+    }
+    
+    /// Drop answers whose entry carries any of these usage labels, e.g.
+    /// "archaic" or "derog.". Returns how many were dropped.
+    pub fn exclude_labels(&mut self, labels: &[&str]) -> Result<usize, String> {
+        let excluded = labels.iter()
+            .map(|label| canonical_label(label).ok_or_else(|| format!("Unknown usage label: {}. Known labels: {}", label, known_labels().join(", "))))
+            .collect::<Result<HashSet<_>, _>>()?;
+        let before = self.words.len();
+        let source = &self.source;
+        self.words.retain(|word| !source.labels(word).iter().any(|label| excluded.contains(label)));
+        Ok(before - self.words.len())
+    }
+    
+    /// Usage labels of a word's entry, e.g. "archaic" or "colloq"
+    pub fn get_labels(&self, word: &str) -> Vec<&'static str> {
+        self.source.labels(&word.to_uppercase())
+    }
+    
+    /// Whether the word source records usage labels; without them
+    /// `exclude_labels` drops nothing
+    pub fn has_labels(&self) -> bool {
+        self.source.has_labels()
+    }
+    
+    /// Check `database` for clues before the word source's own
+    pub fn set_clue_database(&mut self, database: Option<ClueDatabase>) {
+        self.clue_database = database;
+    }
+    
+    pub fn clue_database(&self) -> Option<&ClueDatabase> {
+        self.clue_database.as_ref()
+    }
+    
+    pub fn get_clue(&self, word: &str) -> String {
+        let word = word.to_uppercase();
+        if let Some(entry) = self.clue_database.as_ref().and_then(|db| db.clue(&word)) {
+            return entry.clue.clone();
+        }
+        self.source.clue(&word)
+            .unwrap_or_else(|| MISSING_CLUE.to_string())
+    }
+    
+    /// Every clue for a word, `get_clue`'s first: the clue database's, then
+    /// the word source's senses. Empty when there are none.
+    pub fn get_clues(&self, word: &str) -> Vec<String> {
+        let word = word.to_uppercase();
+        let mut clues: Vec<String> = self.clue_database.as_ref()
+            .map(|db| db.ranked(&word).into_iter().map(|entry| entry.clue.clone()).collect())
+            .unwrap_or_default();
+        for sense in self.source.clues(&word) {
+            if !clues.contains(&sense) {
+                clues.push(sense);
+            }
+        }
+        clues
+    }
+    
+    pub fn stats(&self) -> DictionaryStats {
+        let total_len: usize = self.words.iter().map(|w| w.len()).sum();
+        let avg_len = if self.words.is_empty() {
+            0.0
+        } else {
+            total_len as f32 / self.words.len() as f32
+        };
+        
+        let max_len = self.words.iter().map(|w| w.len()).max().unwrap_or(0);
         
-        // Parse allowlist if provided
-        let allowed_words: Option<std::collections::HashSet<String>> = allowlist.map(|list| {
-            list.lines()
-                .map(|line| line.trim().to_uppercase())
-                .filter(|line| !line.is_empty() && line.chars().all(|c| c.is_ascii_alphabetic()))
-                .collect()
-        });
+        DictionaryStats {
+            word_count: self.words.len(),
+            avg_word_length: avg_len,
+            max_word_length: max_len,
+        }
+    }
+}
+
+/// The Oxford English Dictionary text format: `Word  definition` lines.
+/// The main clue is cleaned up from the first sense of each definition;
+/// the other senses are kept as alternatives.
+pub struct OxfordSource {
+    entries: HashMap<String, String>,
+    words: Vec<String>,
+}
+
+impl OxfordSource {
+    pub fn parse(dict_text: &str) -> Self {
+        let mut entries = HashMap::new();
         
         for line in dict_text.lines() {
             let trimmed = line.trim();
@@ -58,15 +174,7 @@ impl Dictionary {
                                     || (def_lower.starts_with("of ") && def_lower.contains("*"));
                                 
                                 if !is_reference {
-                                    let word_upper = word_clean.to_uppercase();
-                                    // Check allowlist if provided
-                                    if let Some(ref allowed) = allowed_words {
-                                        if allowed.contains(&word_upper) {
-                                            entries.insert(word_upper, definition.to_string());
-                                        }
-                                    } else {
-                                        entries.insert(word_upper, definition.to_string());
-                                    }
+                                    entries.insert(word_clean.to_uppercase(), definition.to_string());
                                 }
                             }
                         }
@@ -75,7 +183,7 @@ impl Dictionary {
             }
         }
         
-        let words: Vec<String> = entries
+        let mut words: Vec<String> = entries
             .iter()
             .filter(|(w, def)| {
                 let len = w.len();
@@ -88,49 +196,94 @@ impl Dictionary {
                     && !def_lower.contains("abbr. ")
                     && !w.ends_with('.');
                 
-                let clue = Self::extract_clue(def);
-                let clean_clue = clue != "Definition not available" 
-                    && !clue.to_lowercase().contains(&w.to_lowercase())
-                    && clue.len() > 10
-                    && !clue.to_lowercase().starts_with("of ")
-                    && !clue.contains(") ")
-                    && !clue.ends_with(")")
-                    && !clue.contains("*");
-                
-                valid_word && not_special && clean_clue
+                valid_word && not_special && Self::is_clean_clue(w, &Self::extract_clue(def))
             })
             .map(|(w, _)| w.clone())
             .collect();
+        // HashMap order differs between runs; keep the word list stable
+        words.sort();
         
-        Dictionary { entries, words }
+        OxfordSource { entries, words }
     }
     
-    pub fn get_words(&self) -> &[String] {
-        &self.words
+    /// Parse dictionary text read at runtime; invalid UTF-8 is replaced
+    pub fn from_bytes(data: &[u8]) -> Self {
+        Self::parse(&String::from_utf8_lossy(data))
     }
     
-    pub fn get_clue(&self, word: &str) -> String {
-        let word_upper = word.to_uppercase();
-        if let Some(def) = self.entries.get(&word_upper) {
-            #[cfg(feature = "debug")]
-            {
-                if def.contains(". b") || def.contains(" b ") {
-                    web_sys::console::log_1(&format!("[DICT] get_clue {}: RAW='{}'", word_upper, def).into());
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path)
+            .map_err(|e| format!("Failed to read dictionary {}: {}", path.display(), e))?;
+        Ok(Self::from_bytes(&data))
+    }
+    
+    /// Whether a cleaned sense is fit to print as a clue for `word`
+    fn is_clean_clue(word: &str, clue: &str) -> bool {
+        clue != MISSING_CLUE
+            && !clue.to_lowercase().contains(&word.to_lowercase())
+            && clue.len() > 10
+            && !clue.to_lowercase().starts_with("of ")
+            && !clue.contains(") ")
+            && !clue.ends_with(")")
+            && !clue.contains("*")
+    }
+    
+    /// Usage labels at the start of any sense, after its part of speech and
+    /// numbering, e.g. "archaic" and "formal" in "adv. archaic or formal 1 from that place"
+    fn usage_labels(definition: &str) -> Vec<&'static str> {
+        let mut labels = Vec::new();
+        for sense in Self::split_senses(definition) {
+            for token in sense.split_whitespace() {
+                let token = token.trim_start_matches(['—', '–', '(']).trim_end_matches([')', ',']);
+                if let Some(label) = canonical_label(token) {
+                    if !labels.contains(&label) {
+                        labels.push(label);
+                    }
+                    continue;
+                }
+                // Parts of speech ("v.tr."), numbering ("2", "b") and joiners
+                let grammar = token.ends_with('.') && token.len() <= 10 && token.chars().all(|c| c.is_ascii_alphabetic() || c == '.');
+                let numbering = !token.is_empty() && token.chars().all(|c| c.is_ascii_digit())
+                    || matches!(token, "a" | "b" | "c" | "d" | "e");
+                if !(grammar || numbering || matches!(token, "&" | "or")) {
+                    break;
                 }
             }
+        }
+        labels
+    }
+    
+    /// Raw text of each sense: numbered ("2 ...") and lettered ("b ...")
+    /// senses, and those of further parts of speech ("—n. ..."). Derived
+    /// words ("—abandonment n.") and the etymology are left out.
+    fn split_senses(definition: &str) -> Vec<&str> {
+        let definition = definition.rfind(" [").map_or(definition, |pos| &definition[..pos]);
+        let mut senses = Vec::new();
+        
+        for (idx, group) in definition.split(" —").enumerate() {
+            let new_part_of_speech = ["n.", "v.", "adj.", "adv.", "prep.", "conj."]
+                .iter()
+                .any(|marker| group.starts_with(marker));
+            if idx > 0 && !new_part_of_speech {
+                break;
+            }
             
-            let clue = Self::extract_clue(def);
-            
-            #[cfg(feature = "debug")]
-            {
-                if clue.contains(" b ") || clue.contains(". b") {
-                    web_sys::console::log_1(&format!("[DICT] WARN {}: FINAL='{}'", word_upper, clue).into());
+            let mut start = 0;
+            let mut search_pos = 0;
+            while let Some(period_pos) = group[search_pos..].find(". ") {
+                let abs_pos = search_pos + period_pos;
+                let after_period = &group[abs_pos + 2..];
+                let marker = after_period.split(' ').next().unwrap_or_default();
+                let numbered = !marker.is_empty() && marker.chars().all(|c| c.is_ascii_digit());
+                if numbered || matches!(marker, "b" | "c" | "d" | "e") {
+                    senses.push(&group[start..abs_pos]);
+                    start = abs_pos + 2;
                 }
+                search_pos = abs_pos + 2;
             }
-            clue
-        } else {
-            "Definition not available".to_string()
+            senses.push(&group[start..]);
         }
+        senses
     }
     
     fn extract_clue(definition: &str) -> String {
@@ -142,7 +295,7 @@ impl Dictionary {
         }
         
         if definition.trim().is_empty() {
-            return "Definition not available".to_string();
+            return MISSING_CLUE.to_string();
         }
         
         let mut def = definition.trim().to_string();
@@ -374,7 +527,7 @@ impl Dictionary {
         def = def.trim_end_matches('.').trim().to_string();
         
         if def.len() < 3 {
-            return "Definition not available".to_string();
+            return MISSING_CLUE.to_string();
         }
         
         // Lowercase FIRST so pattern matching works on capital letters too
@@ -387,17 +540,17 @@ impl Dictionary {
                 def = def[space_pos + 1..].trim().to_string();
             } else {
                 // No content after POS
-                return "Definition not available".to_string();
+                return MISSING_CLUE.to_string();
             }
         }
         
         // If what remains is just labels with no content, reject it
         if def.starts_with("colloq") || def.starts_with("esp") || def.starts_with("usu") {
-            return "Definition not available".to_string();
+            return MISSING_CLUE.to_string();
         }
         
         if def.len() < 10 {
-            return "Definition not available".to_string();
+            return MISSING_CLUE.to_string();
         }
         
         // NOW check for letter enumeration with lowercase patterns
@@ -428,7 +581,7 @@ impl Dictionary {
         def = def.trim().to_string();
         
         if def.len() < 3 {
-            return "Definition not available".to_string();
+            return MISSING_CLUE.to_string();
         }
         
         // Capitalize first letter only
@@ -436,7 +589,7 @@ impl Dictionary {
         if let Some(first) = chars.next() {
             def = first.to_uppercase().collect::<String>() + chars.as_str();
         } else {
-            return "Definition not available".to_string();
+            return MISSING_CLUE.to_string();
         }
         
         // Strip leading POS that got capitalized
@@ -490,21 +643,108 @@ impl Dictionary {
         
         def
     }
+}
+
+impl WordSource for OxfordSource {
+    fn words(&self) -> Vec<String> {
+        self.words.clone()
+    }
     
-    pub fn stats(&self) -> DictionaryStats {
-        let total_len: usize = self.words.iter().map(|w| w.len()).sum();
-        let avg_len = if self.words.is_empty() {
-            0.0
-        } else {
-            total_len as f32 / self.words.len() as f32
-        };
+    fn clue(&self, word: &str) -> Option<String> {
+        let def = self.entries.get(word)?;
+        #[cfg(feature = "debug")]
+        {
+            if def.contains(". b") || def.contains(" b ") {
+                web_sys::console::log_1(&format!("[DICT] get_clue {}: RAW='{}'", word, def).into());
+            }
+        }
         
-        let max_len = self.words.iter().map(|w| w.len()).max().unwrap_or(0);
+        let clue = Self::extract_clue(def);
         
-        DictionaryStats {
-            word_count: self.words.len(),
-            avg_word_length: avg_len,
-            max_word_length: max_len,
+        #[cfg(feature = "debug")]
+        {
+            if clue.contains(" b ") || clue.contains(". b") {
+                web_sys::console::log_1(&format!("[DICT] WARN {}: FINAL='{}'", word, clue).into());
+            }
         }
+        (clue != MISSING_CLUE).then_some(clue)
+    }
+    
+    fn clues(&self, word: &str) -> Vec<String> {
+        let Some(def) = self.entries.get(word) else { return Vec::new() };
+        let mut clues: Vec<String> = self.clue(word).into_iter().collect();
+        for sense in Self::split_senses(def).into_iter().skip(1) {
+            let clue = Self::extract_clue(sense);
+            if Self::is_clean_clue(word, &clue) && !clues.contains(&clue) {
+                clues.push(clue);
+            }
+        }
+        clues
+    }
+    
+    fn labels(&self, word: &str) -> Vec<&'static str> {
+        self.entries.get(word).map(|def| Self::usage_labels(def)).unwrap_or_default()
+    }
+    
+    fn has_labels(&self) -> bool {
+        true
+    }
+}
+
+/// Words of a one-word-per-line list, spelled the way word sources store
+/// them ("ice cream" and "x-ray" become ICECREAM and XRAY)
+fn word_set(list: &str) -> HashSet<String> {
+    list.lines().filter_map(normalize_word).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::word_source::WordList;
+    
+    #[test]
+    fn test_oxford_senses() {
+        let source = OxfordSource::parse(
+            "Abandon  v.tr. 1 give up completely or before completion (abandoned hope). \
+             2 a forsake or desert (a person or a post). b leave or desert (a motor vehicle, ship, etc.). \
+             —n. Lack of inhibition or restraint. —abandonment n. [French]\n\
+             Abbey  n. A building once occupied by monks.\n"
+        );
+        let clues = source.clues("ABANDON");
+        assert_eq!(clues[0], source.clue("ABANDON").unwrap());
+        assert!(clues.contains(&"Forsake or desert".to_string()));
+        assert!(clues.contains(&"Leave or desert".to_string()));
+        assert!(clues.contains(&"Lack of inhibition or restraint".to_string()));
+        assert!(clues.iter().all(|clue| !clue.contains("abandonment")));
+        assert_eq!(source.clues("ABBEY"), ["Building once occupied by monks"]);
+        assert!(source.clues("MISSING").is_empty());
+    }
+    
+    #[test]
+    fn test_usage_labels() {
+        let source = OxfordSource::parse(
+            "Bloke  n. colloq. a man or fellow of no particular note.\n\
+             Cad  n. derog. joc. a man who behaves dishonourably.\n\
+             Thence  adv. archaic or formal 1 from that place or source.\n\
+             Abbey  n. A building once occupied by monks, formal or not.\n"
+        );
+        assert_eq!(source.labels("BLOKE"), ["colloq"]);
+        assert_eq!(source.labels("CAD"), ["derog", "joc"]);
+        assert_eq!(source.labels("THENCE"), ["archaic", "formal"]);
+        assert!(source.labels("ABBEY").is_empty());
+        
+        let mut dict = Dictionary::from_source(Box::new(source), None);
+        assert_eq!(dict.get_words(), ["ABBEY", "BLOKE", "CAD", "THENCE"]);
+        assert_eq!(dict.exclude_labels(&["Derogatory", "archaic"]), Ok(2));
+        assert_eq!(dict.exclude_words("# family edition\nbloke\n"), 1);
+        assert_eq!(dict.get_words(), ["ABBEY"]);
+        assert_eq!(dict.get_labels("cad"), ["derog", "joc"]);
+        assert!(dict.exclude_labels(&["rude"]).is_err());
+        assert!(dict.has_labels());
+        
+        let mut dict = Dictionary::from_source(Box::new(WordList::parse("cat\nice cream\nx-ray\n")), None);
+        assert_eq!(dict.exclude_words("Ice Cream\nX-ray\n"), 2);
+        assert_eq!(dict.get_words(), ["CAT"]);
+        assert!(!dict.has_labels());
     }
 }
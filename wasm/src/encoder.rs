@@ -1,12 +1,14 @@
 use varisat::{CnfFormula, ExtendFormula, Lit, Var};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
 use crate::solution::Placement;
 
 pub struct CrosswordEncoder {
     formula: CnfFormula,
     var_counter: usize,
-    placement_vars: HashMap<(String, usize, usize, bool), Var>,
-    grid_vars: HashMap<(usize, usize, char), Var>,
+    // Ordered maps, so the same words always encode to the same clauses
+    // and a seeded run reproduces its puzzles
+    placement_vars: BTreeMap<(String, usize, usize, bool), Var>,
+    grid_vars: BTreeMap<(usize, usize, char), Var>,
     possible_placements: Vec<Vec<Vec<Vec<Var>>>>,
 }
 
@@ -15,8 +17,8 @@ impl CrosswordEncoder {
         CrosswordEncoder {
             formula: CnfFormula::new(),
             var_counter: 1,
-            placement_vars: HashMap::new(),
-            grid_vars: HashMap::new(),
+            placement_vars: BTreeMap::new(),
+            grid_vars: BTreeMap::new(),
             possible_placements: vec![vec![vec![Vec::new(); 2]; size]; size],
         }
     }
@@ -34,7 +36,7 @@ impl CrosswordEncoder {
         
         let chars: Vec<char> = words.iter()
             .flat_map(|w| w.chars())
-            .collect::<std::collections::HashSet<_>>()
+            .collect::<BTreeSet<_>>()
             .into_iter()
             .collect();
         
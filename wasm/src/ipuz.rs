@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::solution::{Clue, CrosswordPuzzle};
+
+const IPUZ_VERSION: &str = "http://ipuz.org/v2";
+const IPUZ_KIND: &str = "http://ipuz.org/crossword#1";
+const BLOCK: &str = "#";
+
+/// Descriptive ipuz fields. Anything not listed here ends up in
+/// `IpuzDocument::extra` so it survives a read/write round trip.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpuzMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IpuzDimensions {
+    pub width: usize,
+    pub height: usize,
+}
+
+/// A single clue. Reads the `[number, "clue"]`, plain string and object
+/// forms, and always writes the object form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "RawClue")]
+pub struct IpuzClue {
+    pub number: Value,
+    pub clue: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enumeration: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawClue {
+    Pair(Value, String),
+    Plain(String),
+    Full {
+        #[serde(default)]
+        number: Value,
+        #[serde(default)]
+        clue: String,
+        #[serde(default)]
+        enumeration: Option<String>,
+        #[serde(default)]
+        answer: Option<String>,
+        #[serde(flatten)]
+        extra: Map<String, Value>,
+    },
+}
+
+impl From<RawClue> for IpuzClue {
+    fn from(raw: RawClue) -> Self {
+        match raw {
+            RawClue::Pair(number, clue) => IpuzClue {
+                number,
+                clue,
+                enumeration: None,
+                answer: None,
+                extra: Map::new(),
+            },
+            RawClue::Plain(clue) => IpuzClue {
+                number: Value::Null,
+                clue,
+                enumeration: None,
+                answer: None,
+                extra: Map::new(),
+            },
+            RawClue::Full { number, clue, enumeration, answer, extra } => IpuzClue {
+                number,
+                clue,
+                enumeration,
+                answer,
+                extra,
+            },
+        }
+    }
+}
+
+impl IpuzClue {
+    fn number(&self) -> Option<usize> {
+        match &self.number {
+            Value::Number(n) => n.as_u64().map(|n| n as usize),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// An ipuz crossword document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpuzDocument {
+    pub version: String,
+    pub kind: Vec<String>,
+    pub dimensions: IpuzDimensions,
+    pub puzzle: Vec<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<Vec<Vec<Value>>>,
+    #[serde(default)]
+    pub clues: Map<String, Value>,
+    #[serde(flatten)]
+    pub metadata: IpuzMetadata,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Parse an ipuz JSON document
+pub fn parse_ipuz(json: &str) -> Result<IpuzDocument, String> {
+    let doc: IpuzDocument = serde_json::from_str(json)
+        .map_err(|e| format!("Invalid ipuz: {}", e))?;
+
+    if !doc.kind.iter().any(|k| k.starts_with("http://ipuz.org/crossword")) {
+        return Err(format!("Unsupported ipuz kind: {:?}", doc.kind));
+    }
+
+    Ok(doc)
+}
+
+impl IpuzDocument {
+    pub fn from_puzzle(puzzle: &CrosswordPuzzle, metadata: IpuzMetadata) -> Self {
+        let mut doc = IpuzDocument {
+            version: IPUZ_VERSION.to_string(),
+            kind: vec![IPUZ_KIND.to_string()],
+            dimensions: IpuzDimensions { width: 0, height: 0 },
+            puzzle: Vec::new(),
+            solution: None,
+            clues: Map::new(),
+            metadata,
+            extra: Map::new(),
+        };
+        doc.extra.insert("block".to_string(), Value::from(BLOCK));
+        doc.extra.insert("empty".to_string(), Value::from(0));
+        doc.set_puzzle(puzzle);
+        doc
+    }
+
+    /// Replace the grids and clues, keeping metadata, unknown fields and
+    /// per-clue extras for clues whose number and direction still exist.
+    pub fn set_puzzle(&mut self, puzzle: &CrosswordPuzzle) {
+        let size = puzzle.grid.len();
+        self.dimensions = IpuzDimensions { width: size, height: size };
+
+        let numbers = puzzle.cell_numbers();
+
+        self.puzzle = puzzle.grid.iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, cell)| match cell {
+                        Some(_) => Value::from(numbers[y][x].unwrap_or(0)),
+                        None => Value::from(BLOCK),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.solution = Some(puzzle.grid.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Some(ch) => Value::from(ch.to_string()),
+                        None => Value::from(BLOCK),
+                    })
+                    .collect()
+            })
+            .collect());
+
+        for (direction, clues) in [("Across", &puzzle.across_clues), ("Down", &puzzle.down_clues)] {
+            // Clues that no longer parse are rewritten anyway, so only valid
+            // ones can pass on their extras
+            let mut previous: HashMap<usize, IpuzClue> = self.clue_values(direction)
+                .filter_map(|clue| serde_json::from_value::<IpuzClue>(clue.clone()).ok())
+                .filter_map(|c| c.number().map(|n| (n, c)))
+                .collect();
+            self.clues.retain(|key, _| key.split(':').next() != Some(direction));
+
+            let list: Vec<Value> = clues.iter()
+                .map(|clue| {
+                    let extra = previous.remove(&clue.number)
+                        .map(|c| c.extra)
+                        .unwrap_or_default();
+                    let ipuz_clue = IpuzClue {
+                        number: Value::from(clue.number),
+                        clue: clue.clue.clone(),
+                        enumeration: Some(clue.word.chars().count().to_string()),
+                        answer: Some(clue.word.clone()),
+                        extra,
+                    };
+                    serde_json::to_value(ipuz_clue).unwrap_or(Value::Null)
+                })
+                .collect();
+
+            self.clues.insert(direction.to_string(), Value::Array(list));
+        }
+    }
+
+    /// Build a `CrosswordPuzzle` from the solution grid, numbering it the same
+    /// way the generator does and attaching clues by number and direction.
+    pub fn to_puzzle(&self) -> Result<CrosswordPuzzle, String> {
+        let solution = self.solution.as_ref()
+            .ok_or_else(|| "ipuz file has no solution grid".to_string())?;
+
+        let IpuzDimensions { width, height } = self.dimensions;
+        if width != height {
+            return Err(format!("Only square grids are supported (got {}x{})", width, height));
+        }
+        if solution.len() != height || solution.iter().any(|row| row.len() != width) {
+            return Err("ipuz solution does not match its dimensions".to_string());
+        }
+
+        let block = self.extra.get("block")
+            .and_then(|b| b.as_str())
+            .unwrap_or(BLOCK);
+
+        let grid: Vec<Vec<Option<char>>> = solution.iter()
+            .map(|row| row.iter().map(|cell| solution_letter(cell, block)).collect())
+            .collect();
+
+        let placements = CrosswordPuzzle::placements_from_grid(&grid);
+        let mut puzzle = CrosswordPuzzle::from_placements(&placements, width, |_| String::new(), 0);
+        assign_clues(&mut puzzle.across_clues, "Across", self.clue_list("Across")?)?;
+        assign_clues(&mut puzzle.down_clues, "Down", self.clue_list("Down")?)?;
+
+        Ok(puzzle)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Serialization error: {}", e))
+    }
+
+    /// Raw clues for a direction, accepting both "Across" and labelled
+    /// "Across:Heading" keys
+    fn clue_values<'a>(&'a self, direction: &'a str) -> impl Iterator<Item = &'a Value> {
+        self.clues.iter()
+            .filter(move |(key, _)| key.split(':').next() == Some(direction))
+            .filter_map(|(_, list)| list.as_array())
+            .flatten()
+    }
+
+    /// Parsed clues for a direction, naming the first one that is malformed
+    fn clue_list(&self, direction: &str) -> Result<Vec<IpuzClue>, String> {
+        self.clue_values(direction)
+            .enumerate()
+            .map(|(idx, clue)| {
+                serde_json::from_value(clue.clone())
+                    .map_err(|e| format!("{} clue #{} ({}) is invalid: {}", direction, idx + 1, clue, e))
+            })
+            .collect()
+    }
+}
+
+/// Give each entry the clue with its number. A clue whose number matches no
+/// entry of the grid (numbering is derived from the grid) is an error
+/// rather than being dropped.
+fn assign_clues(entries: &mut [Clue], direction: &str, clues: Vec<IpuzClue>) -> Result<(), String> {
+    for clue in clues {
+        let Some(number) = clue.number() else {
+            return Err(format!("{} clue \"{}\" has no number", direction, clue.clue));
+        };
+        let Some(entry) = entries.iter_mut().find(|entry| entry.number == number) else {
+            return Err(format!("Clue {} {} (\"{}\") does not match any entry in the grid", number, direction, clue.clue));
+        };
+        entry.clue = clue.clue;
+    }
+    Ok(())
+}
+
+fn solution_letter(cell: &Value, block: &str) -> Option<char> {
+    let text = match cell {
+        Value::String(s) => s.as_str(),
+        Value::Object(obj) => obj.get("value").and_then(|v| v.as_str()).unwrap_or(""),
+        _ => "",
+    };
+
+    if text == block {
+        return None;
+    }
+    text.chars().next().map(|c| c.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::filled_sample_puzzle;
+
+    #[test]
+    fn test_ipuz_round_trip() {
+        let puzzle = filled_sample_puzzle(|w| format!("Clue for {}", w));
+        let metadata = IpuzMetadata { title: Some("Sample".to_string()), ..Default::default() };
+        let json = IpuzDocument::from_puzzle(&puzzle, metadata).to_json().unwrap();
+
+        let doc = parse_ipuz(&json).unwrap();
+        assert_eq!(doc.metadata.title.as_deref(), Some("Sample"));
+        assert_eq!(doc.puzzle[0][0], Value::from(1));
+        assert_eq!(doc.puzzle[1][1], Value::from("#"));
+
+        let parsed = doc.to_puzzle().unwrap();
+        assert_eq!(parsed.grid, puzzle.grid);
+        assert_eq!(parsed.down_clues[1].number, 2);
+        assert_eq!(parsed.down_clues[1].clue, "Clue for TOT");
+    }
+
+    #[test]
+    fn test_ipuz_preserves_unknown_fields() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": {"width": 3, "height": 3},
+            "puzzle": [[1, 0, 2], [0, "#", 0], [3, 0, 0]],
+            "solution": [["C", "A", "T"], ["A", "#", "O"], ["B", "E", "T"]],
+            "clues": {
+                "Across": [[1, "Feline"], {"number": 3, "clue": "Wager", "highlight": true}],
+                "Down": [[1, "Taxi"], [2, "Toddler"]]
+            },
+            "title": "Tiny",
+            "x-custom": {"keep": "me"}
+        }"##;
+
+        let mut doc = parse_ipuz(json).unwrap();
+        let puzzle = doc.to_puzzle().unwrap();
+        assert_eq!(puzzle.across_clues[1].clue, "Wager");
+
+        doc.set_puzzle(&puzzle);
+        let written: Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        assert_eq!(written["x-custom"]["keep"], "me");
+        assert_eq!(written["title"], "Tiny");
+        assert_eq!(written["clues"]["Across"][1]["highlight"], true);
+        assert_eq!(written["clues"]["Across"][1]["enumeration"], "3");
+
+        // Numbering comes from the grid, so a clue for a missing entry is an error
+        let mismatched = json.replace("[2, \"Toddler\"]", "[4, \"Toddler\"]");
+        assert_eq!(parse_ipuz(&mismatched).unwrap().to_puzzle().unwrap_err(),
+                   "Clue 4 Down (\"Toddler\") does not match any entry in the grid");
+
+        let malformed = json.replace("[2, \"Toddler\"]", "[2, 7]");
+        assert!(parse_ipuz(&malformed).unwrap().to_puzzle().unwrap_err().starts_with("Down clue #2 ([2,7]) is invalid"));
+    }
+}
@@ -1,17 +1,37 @@
 // Core modules - always compiled
 mod dictionary;
+mod word_source;
+mod clue_db;
 mod encoder;
 mod solver;
 mod solution;
+mod puz;
+mod ipuz;
+mod svg;
+mod text;
+mod ascii_grid;
+mod book;
+mod pdf;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 
 #[macro_use]
 mod debug;
 
 // Re-export for CLI use
-pub use dictionary::Dictionary;
+pub use dictionary::{Dictionary, DictionaryStats, OxfordSource};
+pub use clue_db::{ClueDatabase, ClueEntry, ClueRotation, Difficulty};
+pub use word_source::{canonical_label, known_labels, load_word_source, ClueTable, DictionaryFormat, WordList, WordSource, MISSING_CLUE};
 pub use encoder::CrosswordEncoder;
 pub use solver::{solve_with_iterations, solve_encoded};
 pub use solution::{Placement, Clue, CrosswordPuzzle, CrosswordMetadata};
+pub use puz::{parse_puz, PuzFile};
+pub use ipuz::{parse_ipuz, IpuzDocument, IpuzMetadata, IpuzClue, IpuzDimensions};
+pub use svg::{render_puzzle_svg, render_solution_svg, SvgOptions};
+pub use ascii_grid::parse_ascii_grid;
+pub use text::{render_puzzle_text, render_solution_text, TextOptions, TextStyle};
+pub use book::{drop_cap, BookConfig, Chapter, ClueRecord, CrosswordBook, Direction, KdpFormat, Margins, Section, TrimSize, INTRODUCTION, INTRODUCTION_TITLES};
+pub use pdf::PdfGenerator;
 
 // WASM-specific code - only when wasm feature enabled
 #[cfg(feature = "wasm")]
@@ -42,12 +62,14 @@ mod wasm_interface {
 
     static DICTIONARY: Mutex<Option<Dictionary>> = Mutex::new(None);
     static ENCODER_STATE: Mutex<Option<(CrosswordEncoder, Vec<String>, usize)>> = Mutex::new(None);
+    static CLUE_ROTATION: Mutex<Option<ClueRotation>> = Mutex::new(None);
 
     #[wasm_bindgen(start)]
     pub fn main() {
         std::panic::set_hook(Box::new(console_error_panic_hook::hook));
     }
 
+    #[cfg(feature = "embedded-dictionary")]
     #[wasm_bindgen]
     pub fn initialize() -> Result<JsValue, JsValue> {
         use crate::debug_log;
@@ -56,7 +78,91 @@ mod wasm_interface {
         
         // Use embedded clean allowlist for web to keep it family-friendly
         let allowlist = include_str!("../clean_allowlist.txt");
-        let dict = Dictionary::with_allowlist(Some(allowlist));
+        set_dictionary(Dictionary::with_allowlist(Some(allowlist)))
+    }
+
+    /// Load a dictionary fetched at runtime. `format` is oxford, words or
+    /// tsv (default: detected). Oxford text is narrowed to the built-in
+    /// family-friendly allowlist unless `allowlist` is given.
+    #[wasm_bindgen]
+    pub fn initialize_with_dictionary(text: &str, format: Option<String>, allowlist: Option<String>) -> Result<JsValue, JsValue> {
+        let format = match format {
+            Some(format) => format.parse::<DictionaryFormat>().map_err(|e| JsValue::from_str(&e))?,
+            None => DictionaryFormat::detect(text),
+        };
+        debug_log!("[WASM] Initializing {} dictionary from {} bytes...", format.as_str(), text.len());
+        
+        let allowlist = match (allowlist.as_deref(), format) {
+            (Some(list), _) => Some(list),
+            (None, DictionaryFormat::Oxford) => Some(include_str!("../clean_allowlist.txt")),
+            (None, _) => None,
+        };
+        set_dictionary(Dictionary::from_source(format.parse(text), allowlist))
+    }
+
+    /// Use editor clues before dictionary definitions. `format` is tsv or
+    /// json (default: tsv); `difficulty` picks easy, medium or hard clues
+    /// where an answer has several. Returns the number of answers with clues.
+    #[wasm_bindgen]
+    pub fn set_clue_database(text: &str, format: Option<String>, difficulty: Option<String>) -> Result<usize, JsValue> {
+        let mut database = match format.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("tsv") => ClueDatabase::parse_tsv(text),
+            Some("json") => ClueDatabase::parse_json(text),
+            Some(other) => Err(format!("Invalid clue format: {}. Use tsv or json", other)),
+        }.map_err(|e| JsValue::from_str(&e))?;
+        if let Some(difficulty) = difficulty {
+            database.set_difficulty(Some(difficulty.parse().map_err(|e: String| JsValue::from_str(&e))?));
+        }
+        let answers = database.len();
+        
+        let mut dict_lock = DICTIONARY.lock()
+            .map_err(|e| JsValue::from_str(&format!("Lock error: {}", e)))?;
+        let dict = dict_lock.as_mut()
+            .ok_or_else(|| JsValue::from_str("Dictionary not initialized"))?;
+        dict.set_clue_database(Some(database));
+        debug_log!("[WASM] Clue database: {} answers", answers);
+        Ok(answers)
+    }
+
+    /// Drop answers for family and classroom sets: `exclude_labels` is a
+    /// comma-separated list of usage labels (e.g. "archaic,derog") and
+    /// `blocklist` has one word per line. Returns the words left.
+    #[wasm_bindgen]
+    pub fn filter_dictionary(exclude_labels: Option<String>, blocklist: Option<String>) -> Result<usize, JsValue> {
+        let mut dict_lock = DICTIONARY.lock()
+            .map_err(|e| JsValue::from_str(&format!("Lock error: {}", e)))?;
+        let dict = dict_lock.as_mut()
+            .ok_or_else(|| JsValue::from_str("Dictionary not initialized"))?;
+        
+        if let Some(labels) = exclude_labels {
+            let labels: Vec<&str> = labels.split(',').map(str::trim).filter(|l| !l.is_empty()).collect();
+            dict.exclude_labels(&labels).map_err(|e| JsValue::from_str(&e))?;
+        }
+        if let Some(blocklist) = blocklist {
+            dict.exclude_words(&blocklist);
+        }
+        debug_log!("[WASM] Filtered dictionary: {} words left", dict.get_words().len());
+        Ok(dict.get_words().len())
+    }
+
+    /// Forget which clues earlier puzzles used, e.g. when starting a new set
+    #[wasm_bindgen]
+    pub fn reset_clue_rotation() {
+        if let Ok(mut rotation) = CLUE_ROTATION.lock() {
+            *rotation = None;
+        }
+    }
+
+    /// A clue for `word` that no puzzle this session has shown yet, while
+    /// the dictionary and clue database have one
+    fn rotated_clue(dict: &Dictionary, word: &str) -> String {
+        let clues = dict.get_clues(word);
+        let rotated = CLUE_ROTATION.lock().ok()
+            .and_then(|mut rotation| rotation.get_or_insert_with(ClueRotation::new).take(word, &clues));
+        rotated.unwrap_or_else(|| dict.get_clue(word))
+    }
+
+    fn set_dictionary(dict: Dictionary) -> Result<JsValue, JsValue> {
         let stats = dict.stats();
         
         debug_log!("[WASM] Dictionary loaded: {} words (filtered)", stats.word_count);
@@ -249,7 +355,7 @@ mod wasm_interface {
         let puzzle = CrosswordPuzzle::from_placements(
             &placements,
             size,
-            |word| dict.get_clue(word),
+            |word| rotated_clue(dict, word),
             elapsed_ms,
         );
         
@@ -329,7 +435,7 @@ mod wasm_interface {
             let puzzle = CrosswordPuzzle::from_placements(
                 &placements,
                 size,
-                |word| dict.get_clue(word),
+                |word| rotated_clue(dict, word),
                 elapsed_ms,
             );
             
@@ -352,6 +458,80 @@ mod wasm_interface {
             }
         }
     }
+
+    #[wasm_bindgen]
+    pub fn puzzle_to_ipuz(puzzle: JsValue, title: Option<String>) -> Result<String, JsValue> {
+        let puzzle: CrosswordPuzzle = serde_wasm_bindgen::from_value(puzzle)
+            .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
+        
+        let metadata = IpuzMetadata {
+            title,
+            ..Default::default()
+        };
+        
+        IpuzDocument::from_puzzle(&puzzle, metadata)
+            .to_json()
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn ipuz_to_puzzle(json: &str) -> Result<JsValue, JsValue> {
+        let puzzle = parse_ipuz(json)
+            .and_then(|doc| doc.to_puzzle())
+            .map_err(|e| JsValue::from_str(&e))?;
+        
+        serde_wasm_bindgen::to_value(&puzzle)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    #[wasm_bindgen]
+    pub fn puzzle_to_svg(puzzle: JsValue, solution: bool, options: JsValue) -> Result<String, JsValue> {
+        let puzzle: CrosswordPuzzle = serde_wasm_bindgen::from_value(puzzle)
+            .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
+        
+        let options: SvgOptions = if options.is_undefined() || options.is_null() {
+            SvgOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)
+                .map_err(|e| JsValue::from_str(&format!("Invalid SVG options: {}", e)))?
+        };
+        
+        if solution {
+            Ok(render_solution_svg(&puzzle, &options))
+        } else {
+            Ok(render_puzzle_svg(&puzzle, &options))
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn puzzle_to_text(puzzle: JsValue, solution: bool, options: JsValue) -> Result<String, JsValue> {
+        let puzzle: CrosswordPuzzle = serde_wasm_bindgen::from_value(puzzle)
+            .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
+        
+        let options: TextOptions = if options.is_undefined() || options.is_null() {
+            TextOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)
+                .map_err(|e| JsValue::from_str(&format!("Invalid text options: {}", e)))?
+        };
+        
+        if solution {
+            Ok(render_solution_text(&puzzle, &options))
+        } else {
+            Ok(render_puzzle_text(&puzzle, &options))
+        }
+    }
+
+    /// Typeset a book, in the JSON layout the CLI saves, as PDF bytes
+    #[wasm_bindgen]
+    pub fn book_to_pdf(book: JsValue) -> Result<Vec<u8>, JsValue> {
+        let book: CrosswordBook = serde_wasm_bindgen::from_value(book)
+            .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
+        
+        PdfGenerator::new()
+            .generate_document(&book)
+            .map_err(|e| JsValue::from_str(&e))
+    }
 }
 
 #[cfg(feature = "wasm")]
@@ -0,0 +1,877 @@
+use crate::book::{drop_cap, BookConfig, Chapter, CrosswordBook, Section, INTRODUCTION};
+use crate::solution::{Clue, CrosswordPuzzle};
+use std::ops::Range;
+
+const POINTS_PER_INCH: f32 = 72.0;
+const POINTS_PER_CM: f32 = 28.35;
+
+// LaTeX size commands at 11pt
+const SIZE_NORMAL: f32 = 11.0;
+const SIZE_LARGE: f32 = 12.0;
+const SIZE_SUBSECTION: f32 = 14.4;
+const SIZE_PUZZLE_TITLE: f32 = 17.28;
+const SIZE_CHAPTER: f32 = 24.88;
+
+/// Smallest clue text before clues continue on another page
+const MIN_CLUE_SIZE: f32 = 6.0;
+
+/// Renders a `CrosswordBook` straight to PDF with the same interior as the
+/// LaTeX output, using only the standard PDF fonts, so neither the CLI nor
+/// the web build needs a TeX installation.
+pub struct PdfGenerator {}
+
+impl PdfGenerator {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn generate_document(&self, book: &CrosswordBook) -> Result<Vec<u8>, String> {
+        let config = book.config();
+        let layout = PageLayout::new(config);
+
+        // Main matter first so the table of contents knows its page numbers
+        let mut main = Vec::new();
+        main.extend(self.generate_introduction(&layout, config));
+
+        // Each section opens with a heading page on the right, as in the
+        // LaTeX output; either way clue pages stay on the left and grids on
+        // the right
+        let chapters = book.chapters();
+        let mut chapter_starts = Vec::new();
+        for chapter in &chapters {
+            match chapter.section {
+                Some(section) => {
+                    if main.len() % 2 == 1 {
+                        main.push(Canvas::new());
+                    }
+                    chapter_starts.push(main.len() + 1);
+                    main.extend(self.generate_section_opener(&layout, section, chapter));
+                    if main.len() % 2 == 0 {
+                        main.push(Canvas::new());
+                    }
+                }
+                None => {
+                    if main.len() % 2 == 0 {
+                        main.push(Canvas::new());
+                    }
+                    chapter_starts.push(main.len() + 1);
+                }
+            }
+            for (offset, puzzle) in chapter.puzzles.iter().enumerate() {
+                let mut clue_pages = self.generate_clue_pages(&layout, puzzle, chapter.first_number + offset).into_iter();
+                main.extend(clue_pages.next());
+                main.push(self.generate_grid_page(&layout, puzzle));
+                // Clues that still do not fit continue on the next left-hand pages
+                for page in clue_pages {
+                    main.push(page);
+                    main.push(Canvas::new());
+                }
+            }
+        }
+
+        // Answer key opens on a right-hand (odd) page
+        if main.len() % 2 == 1 {
+            main.push(Canvas::new());
+        }
+        let answer_key_start = main.len() + 1;
+        main.extend(self.generate_answer_key(&layout, &chapters));
+
+        let mut front = vec![
+            self.generate_title_page(&layout, config),
+            self.generate_copyright_page(&layout, config),
+            self.generate_toc(&layout, &chapters, &chapter_starts, answer_key_start),
+        ];
+        if front.len() % 2 == 1 {
+            front.push(Canvas::new());
+        }
+
+        let mut pages: Vec<String> = front.into_iter().map(|c| c.content).collect();
+        for (idx, mut canvas) in main.into_iter().enumerate() {
+            // Page numbers in the outer corner, skipping blank pages
+            let number = idx + 1;
+            if !canvas.content.is_empty() {
+                let label = number.to_string();
+                let x = if number % 2 == 1 {
+                    layout.right(number) - text_width(&label, Font::Roman, SIZE_NORMAL)
+                } else {
+                    layout.left(number)
+                };
+                canvas.text(x, layout.header_y(), Font::Roman, SIZE_NORMAL, &label);
+            }
+            pages.push(canvas.content);
+        }
+
+        Ok(write_pdf(&pages, &layout, config))
+    }
+
+    fn generate_title_page(&self, layout: &PageLayout, config: &BookConfig) -> Canvas {
+        let mut canvas = Canvas::new();
+        let page = 1;
+        let center = layout.center_x(page);
+        let width = layout.text_width();
+        let mut y = layout.top() - 2.0 * POINTS_PER_CM;
+
+        y = canvas.centered_paragraph(center, y, width, Font::Bold, SIZE_CHAPTER, &config.title);
+        y -= 0.8 * POINTS_PER_CM;
+
+        if let Some(ref subtitle) = config.subtitle {
+            y = canvas.centered_paragraph(center, y, width, Font::Roman, SIZE_PUZZLE_TITLE, subtitle);
+            y -= POINTS_PER_CM;
+        }
+
+        if let Some(ref desc) = config.description {
+            canvas.centered_paragraph(center, y, width, Font::Italic, SIZE_SUBSECTION, desc);
+        }
+
+        if let Some(ref author) = config.author {
+            let y = layout.bottom() + 2.0 * POINTS_PER_CM;
+            canvas.centered_paragraph(center, y, width, Font::Roman, SIZE_PUZZLE_TITLE, author);
+        }
+
+        canvas
+    }
+
+    fn generate_copyright_page(&self, layout: &PageLayout, config: &BookConfig) -> Canvas {
+        let page = 2;
+        let center = layout.center_x(page);
+        let width = layout.text_width();
+
+        // Lay the block out once to measure it, then again vertically centred
+        let draw = |canvas: &mut Canvas, top: f32| -> f32 {
+            let mut y = top;
+
+            if let Some(ref year) = config.copyright_year {
+                let line = format!("Copyright © {} {}", year, config.author.as_deref().unwrap_or(""));
+                y = canvas.centered_paragraph(center, y, width, Font::Roman, SIZE_NORMAL, line.trim());
+            }
+            y -= 1.5 * POINTS_PER_CM;
+
+            y = canvas.centered_paragraph(center, y, width, Font::Roman, SIZE_NORMAL, "All rights reserved.");
+            y -= 0.8 * POINTS_PER_CM;
+
+            y = canvas.centered_paragraph(
+                center, y, width * 0.8, Font::Roman, SIZE_NORMAL,
+                "No part of this publication may be reproduced, distributed, or transmitted in any form or by any means, without the prior written permission of the publisher.",
+            );
+            y -= POINTS_PER_CM;
+
+            if let Some(ref edition) = config.edition {
+                y = canvas.centered_paragraph(center, y, width, Font::Roman, SIZE_NORMAL, edition);
+                y -= 0.5 * POINTS_PER_CM;
+            }
+
+            if let Some(ref isbn) = config.isbn {
+                y = canvas.centered_paragraph(center, y, width, Font::Roman, SIZE_NORMAL, &format!("ISBN: {}", isbn));
+                y -= 0.5 * POINTS_PER_CM;
+            }
+
+            if let Some(ref publisher) = config.publisher {
+                y = canvas.centered_paragraph(center, y, width, Font::Roman, SIZE_NORMAL, &format!("Published by {}", publisher));
+            }
+
+            y
+        };
+
+        let block_height = layout.top() - draw(&mut Canvas::new(), layout.top());
+        let available = layout.top() - layout.bottom();
+        let mut canvas = Canvas::new();
+        draw(&mut canvas, layout.top() - ((available - block_height) / 2.0).max(0.0));
+        canvas
+    }
+
+    fn generate_toc(&self, layout: &PageLayout, chapters: &[Chapter], chapter_starts: &[usize], answer_key_start: usize) -> Canvas {
+        let mut canvas = Canvas::new();
+        let page = 3;
+        let left = layout.left(page);
+        let right = layout.right(page);
+        let mut y = layout.top() - SIZE_SUBSECTION;
+
+        let title = "Contents";
+        let title_x = layout.center_x(page) - text_width(title, Font::Bold, SIZE_SUBSECTION) / 2.0;
+        canvas.text(title_x, y, Font::Bold, SIZE_SUBSECTION, title);
+        y -= POINTS_PER_CM + 2.0 * SIZE_NORMAL;
+
+        let mut entries = vec![("Introduction".to_string(), 1)];
+        for (chapter, &start) in chapters.iter().zip(chapter_starts) {
+            let numbers = if chapter.puzzles.len() == 1 {
+                chapter.first_number.to_string()
+            } else {
+                format!("{}–{}", chapter.first_number, chapter.last_number())
+            };
+            let title = chapter.section.map_or("Puzzles", |section| section.title.as_str());
+            entries.push((format!("{} ({})", title, numbers), start));
+        }
+        entries.push(("Answer Key".to_string(), answer_key_start));
+
+        for (label, page_number) in entries {
+            let number = page_number.to_string();
+            let label_end = left + text_width(&label, Font::Roman, SIZE_NORMAL);
+            let number_x = right - text_width(&number, Font::Roman, SIZE_NORMAL);
+            canvas.text(left, y, Font::Roman, SIZE_NORMAL, &label);
+            canvas.dot_leader(label_end, number_x, y, SIZE_NORMAL);
+            canvas.text(number_x, y, Font::Roman, SIZE_NORMAL, &number);
+            y -= 2.0 * SIZE_NORMAL;
+        }
+
+        canvas
+    }
+
+    fn generate_introduction(&self, layout: &PageLayout, config: &BookConfig) -> Vec<Canvas> {
+        let mut pages = Vec::new();
+        let mut canvas = Canvas::new();
+        let mut page = 1;
+        let line_height = SIZE_NORMAL * 1.2;
+        let parskip = 0.8 * SIZE_NORMAL;
+        let parindent = 1.5 * SIZE_NORMAL;
+
+        let mut y = canvas.chapter_heading(layout, page, "Introduction");
+
+        // Drop cap spanning the first three lines
+        let (initial, opening) = drop_cap(INTRODUCTION[0]);
+        let drop_size = line_height * 3.0;
+        let drop_width = text_width(initial, Font::Roman, drop_size) + 2.0;
+        canvas.text(layout.left(page), y - 2.0 * line_height, Font::Roman, drop_size, initial);
+
+        for (idx, paragraph) in INTRODUCTION.iter().enumerate() {
+            let paragraph = if idx == 0 { opening } else { paragraph };
+            let indent_first = if idx == 0 { 0.0 } else { parindent };
+            let lines = wrap_text_with(paragraph, Font::Roman, SIZE_NORMAL, |line| {
+                let width = layout.text_width();
+                if idx == 0 && line < 3 {
+                    width - drop_width
+                } else if line == 0 {
+                    width - indent_first
+                } else {
+                    width
+                }
+            });
+
+            for (line_idx, line) in lines.iter().enumerate() {
+                if y - line_height < layout.bottom() {
+                    pages.push(std::mem::replace(&mut canvas, Canvas::new()));
+                    page += 1;
+                    y = layout.top();
+                }
+                let x = if idx == 0 && line_idx < 3 {
+                    layout.left(page) + drop_width
+                } else if line_idx == 0 {
+                    layout.left(page) + indent_first
+                } else {
+                    layout.left(page)
+                };
+                y -= line_height;
+                canvas.text(x, y, Font::Roman, SIZE_NORMAL, line);
+            }
+            y -= parskip;
+        }
+
+        y -= 1.5 * POINTS_PER_CM;
+        canvas.text(layout.left(page), y, Font::Roman, SIZE_NORMAL, "Happy solving!");
+
+        if let Some(ref author) = config.author {
+            y -= 0.8 * POINTS_PER_CM + line_height;
+            let x = layout.right(page) - text_width(author, Font::Italic, SIZE_NORMAL);
+            canvas.text(x, y, Font::Italic, SIZE_NORMAL, author);
+        }
+
+        pages.push(canvas);
+        pages
+    }
+
+    /// A section's heading page, always a right-hand (odd) page; a long
+    /// intro continues on the pages after it
+    fn generate_section_opener(&self, layout: &PageLayout, section: &Section, chapter: &Chapter) -> Vec<Canvas> {
+        let mut pages = Vec::new();
+        let mut page = 1;
+        let width = layout.text_width();
+        let line_height = SIZE_NORMAL * 1.2;
+        let mut canvas = Canvas::new();
+
+        let mut y = canvas.chapter_heading(layout, page, &section.title);
+        if let Some(summary) = chapter.summary() {
+            y -= SIZE_LARGE;
+            canvas.text(layout.left(page), y, Font::Roman, SIZE_LARGE, &summary);
+        }
+
+        y -= POINTS_PER_CM;
+        for paragraph in section.intro_paragraphs() {
+            for line in wrap_text(paragraph, Font::Roman, SIZE_NORMAL, width) {
+                if y - line_height < layout.bottom() {
+                    pages.push(std::mem::replace(&mut canvas, Canvas::new()));
+                    page += 1;
+                    y = layout.top();
+                }
+                y -= line_height;
+                canvas.text(layout.left(page), y, Font::Roman, SIZE_NORMAL, &line);
+            }
+            y -= 0.8 * SIZE_NORMAL;
+        }
+
+        pages.push(canvas);
+        pages
+    }
+
+    /// The clue pages of a puzzle. The text shrinks down to `MIN_CLUE_SIZE`
+    /// to fit one page; clues that still do not fit continue on more pages.
+    fn generate_clue_pages(&self, layout: &PageLayout, puzzle: &CrosswordPuzzle, number: usize) -> Vec<Canvas> {
+        // Clue pages are always left-hand (even) pages
+        let page = 2;
+        let column_width = layout.text_width() * 0.48;
+        let across_x = layout.left(page);
+        let down_x = layout.right(page) - column_width;
+        let title_y = layout.top() - SIZE_PUZZLE_TITLE;
+        let columns_top = title_y - 0.5 * POINTS_PER_CM - SIZE_NORMAL;
+
+        // Shrink the clue text until both columns fit on the page
+        let mut size = SIZE_NORMAL;
+        while size > MIN_CLUE_SIZE {
+            let available = columns_top - layout.bottom();
+            let across = clue_column_height(&puzzle.across_clues, column_width, size);
+            let down = clue_column_height(&puzzle.down_clues, column_width, size);
+            if across.max(down) <= available {
+                break;
+            }
+            size -= 0.5;
+        }
+
+        let mut pages = Vec::new();
+        let mut across = puzzle.across_clues.as_slice();
+        let mut down = puzzle.down_clues.as_slice();
+        loop {
+            let mut canvas = Canvas::new();
+            let title = if pages.is_empty() {
+                format!("Puzzle {}", number)
+            } else {
+                format!("Puzzle {} (continued)", number)
+            };
+            canvas.text(layout.left(page), title_y, Font::Bold, SIZE_PUZZLE_TITLE, &title);
+            let span = layout.bottom()..columns_top;
+            across = &across[canvas.clue_column(across_x, column_width, span.clone(), size, "Across", across)..];
+            down = &down[canvas.clue_column(down_x, column_width, span, size, "Down", down)..];
+            pages.push(canvas);
+            if across.is_empty() && down.is_empty() {
+                return pages;
+            }
+        }
+    }
+
+    fn generate_grid_page(&self, layout: &PageLayout, puzzle: &CrosswordPuzzle) -> Canvas {
+        // Grid pages are always right-hand (odd) pages
+        let page = 1;
+        let size = puzzle.grid.len();
+        let grid_width = layout.text_width() * 0.95;
+        let cell = grid_width / size as f32;
+        let x = layout.center_x(page) - grid_width / 2.0;
+        let y = layout.top() - 0.5 * POINTS_PER_CM;
+
+        let mut canvas = Canvas::new();
+        canvas.puzzle_grid(puzzle, x, y, cell, false);
+        canvas
+    }
+
+    fn generate_answer_key(&self, layout: &PageLayout, chapters: &[Chapter]) -> Vec<Canvas> {
+        let mut pages = Vec::new();
+        let column_width = layout.text_width() * 0.48;
+        let label_height = SIZE_LARGE + 0.3 * POINTS_PER_CM;
+        let row_gap = POINTS_PER_CM;
+
+        // 4 puzzles per page, 2x2 grid; each section starts a new page under its own heading
+        for chapter in chapters {
+            for (page_idx, chunk) in chapter.puzzles.chunks(4).enumerate() {
+                let page = 1 + pages.len();
+                let mut canvas = Canvas::new();
+
+                let mut top = if pages.is_empty() {
+                    canvas.chapter_heading(layout, page, "Answer Key")
+                } else {
+                    layout.top()
+                };
+                if let Some(section) = chapter.section.filter(|_| page_idx == 0) {
+                    top -= SIZE_SUBSECTION;
+                    canvas.text(layout.left(page), top, Font::Bold, SIZE_SUBSECTION, &section.title);
+                    top -= 0.5 * POINTS_PER_CM;
+                }
+
+                let available = top - layout.bottom();
+                let grid_width = (column_width * 0.85)
+                    .min((available - 2.0 * label_height - row_gap) / 2.0);
+
+                for (chunk_idx, puzzle) in chunk.iter().enumerate() {
+                    let puzzle_num = chapter.first_number + page_idx * 4 + chunk_idx;
+                    let column_x = if chunk_idx % 2 == 0 {
+                        layout.left(page)
+                    } else {
+                        layout.right(page) - column_width
+                    };
+                    let center = column_x + column_width / 2.0;
+                    let row_top = top - (chunk_idx / 2) as f32 * (label_height + grid_width + row_gap);
+
+                    let label = format!("Puzzle {}", puzzle_num);
+                    let label_x = center - text_width(&label, Font::Bold, SIZE_LARGE) / 2.0;
+                    canvas.text(label_x, row_top - SIZE_LARGE, Font::Bold, SIZE_LARGE, &label);
+
+                    let cell = grid_width / puzzle.grid.len() as f32;
+                    canvas.puzzle_grid(puzzle, center - grid_width / 2.0, row_top - label_height, cell, true);
+                }
+
+                pages.push(canvas);
+            }
+        }
+
+        pages
+    }
+}
+
+impl Default for PdfGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Page geometry in points, mirroring the LaTeX geometry settings
+struct PageLayout {
+    width: f32,
+    height: f32,
+    top: f32,
+    bottom: f32,
+    inner: f32,
+    outer: f32,
+}
+
+impl PageLayout {
+    fn new(config: &BookConfig) -> Self {
+        let margins = config.margins();
+        Self {
+            width: config.trim_size.width * POINTS_PER_INCH,
+            height: config.trim_size.height * POINTS_PER_INCH,
+            top: margins.top * POINTS_PER_INCH,
+            bottom: margins.bottom * POINTS_PER_INCH,
+            inner: margins.inner * POINTS_PER_INCH,
+            outer: margins.outer * POINTS_PER_INCH,
+        }
+    }
+
+    /// Left edge of the text block; the gutter is on the left of odd pages
+    fn left(&self, page: usize) -> f32 {
+        if page % 2 == 1 { self.inner } else { self.outer }
+    }
+
+    fn right(&self, page: usize) -> f32 {
+        self.left(page) + self.text_width()
+    }
+
+    fn center_x(&self, page: usize) -> f32 {
+        self.left(page) + self.text_width() / 2.0
+    }
+
+    fn text_width(&self) -> f32 {
+        self.width - self.inner - self.outer
+    }
+
+    fn top(&self) -> f32 {
+        self.height - self.top
+    }
+
+    fn bottom(&self) -> f32 {
+        self.bottom
+    }
+
+    fn header_y(&self) -> f32 {
+        self.top() + 0.4 * POINTS_PER_INCH
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Font {
+    Roman,
+    Bold,
+    Italic,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Roman => "F1",
+            Font::Bold => "F2",
+            Font::Italic => "F3",
+        }
+    }
+
+    fn widths(self) -> &'static [u16; 95] {
+        match self {
+            Font::Bold => &TIMES_BOLD_WIDTHS,
+            // Times-Italic is close enough to Times-Roman for line breaking
+            Font::Roman | Font::Italic => &TIMES_ROMAN_WIDTHS,
+        }
+    }
+}
+
+/// A page's content stream under construction
+struct Canvas {
+    content: String,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Self { content: String::new() }
+    }
+
+    fn text(&mut self, x: f32, y: f32, font: Font, size: f32, text: &str) {
+        self.content.push_str(&format!(
+            "BT /{} {:.2} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+            font.resource(),
+            size,
+            x,
+            y,
+            pdf_string(text)
+        ));
+    }
+
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, line_width: f32) {
+        self.content.push_str(&format!(
+            "{:.2} w {:.2} {:.2} {:.2} {:.2} re S\n",
+            line_width, x, y, w, h
+        ));
+    }
+
+    fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.content.push_str(&format!("{:.2} {:.2} {:.2} {:.2} re f\n", x, y, w, h));
+    }
+
+    /// Draw wrapped, centred text with its first baseline one line below `top`;
+    /// returns the y position under the last line
+    fn centered_paragraph(&mut self, center: f32, top: f32, width: f32, font: Font, size: f32, text: &str) -> f32 {
+        let mut y = top;
+        for line in wrap_text(text, font, size, width) {
+            y -= size * 1.2;
+            let x = center - text_width(&line, font, size) / 2.0;
+            self.text(x, y, font, size, &line);
+        }
+        y
+    }
+
+    fn dot_leader(&mut self, from: f32, to: f32, y: f32, size: f32) {
+        let dot_width = text_width(" .", Font::Roman, size);
+        let count = ((to - from) / dot_width).floor() as usize;
+        if count > 2 {
+            let dots = " .".repeat(count - 1);
+            let x = to - text_width(&dots, Font::Roman, size) - dot_width / 2.0;
+            self.text(x, y, Font::Roman, size, &dots);
+        }
+    }
+
+    /// Unnumbered chapter heading (as in `\chapter*`); returns the y to continue from
+    fn chapter_heading(&mut self, layout: &PageLayout, page: usize, title: &str) -> f32 {
+        let y = layout.top() - SIZE_CHAPTER;
+        self.text(layout.left(page), y, Font::Bold, SIZE_CHAPTER, title);
+        y - 20.0 - SIZE_NORMAL
+    }
+
+    /// Draw as many of `clues` as fit in the vertical `span` (bottom..top)
+    /// under a heading and return how many were drawn. An empty list draws
+    /// nothing; at least one clue is always drawn so a continued column
+    /// makes progress.
+    fn clue_column(&mut self, x: f32, width: f32, span: Range<f32>, size: f32, heading: &str, clues: &[Clue]) -> usize {
+        if clues.is_empty() {
+            return 0;
+        }
+        let line_height = size * 1.2;
+        let label_width = text_width("00.", Font::Roman, size) + size * 0.5;
+        let mut y = span.end - SIZE_SUBSECTION;
+
+        self.text(x, y, Font::Bold, SIZE_SUBSECTION, heading);
+        y -= SIZE_SUBSECTION * 0.6;
+
+        for (idx, clue) in clues.iter().enumerate() {
+            let label = format!("{}.", clue.number);
+            let lines = wrap_text(&clue.clue, Font::Roman, size, width - label_width);
+            let height = lines.len().max(1) as f32 * line_height + size * 0.2;
+            if idx > 0 && y - height < span.start {
+                return idx;
+            }
+            y -= line_height + size * 0.2;
+            let label_x = x + label_width - size * 0.5 - text_width(&label, Font::Roman, size);
+            self.text(label_x, y, Font::Roman, size, &label);
+            for (idx, line) in lines.iter().enumerate() {
+                if idx > 0 {
+                    y -= line_height;
+                }
+                self.text(x + label_width, y, Font::Roman, size, line);
+            }
+        }
+        clues.len()
+    }
+
+    /// Draw a grid with its top-left corner at (x, y). Blank puzzles get
+    /// clue numbers; answer grids get letters.
+    fn puzzle_grid(&mut self, puzzle: &CrosswordPuzzle, x: f32, y: f32, cell: f32, answers: bool) {
+        let size = puzzle.grid.len();
+        let line_width = if answers || size > 14 { 0.5 } else { 1.0 };
+
+        let numbers = puzzle.cell_numbers();
+
+        // Smaller numbers for larger grids
+        let number_size: f32 = if size > 14 { 6.0 } else { 8.0 };
+        let number_size = number_size.min(cell * 0.35);
+        let letter_size = (9.0_f32).min(cell * 0.7);
+
+        for (row, cells) in puzzle.grid.iter().enumerate() {
+            for (col, letter) in cells.iter().enumerate() {
+                let cx = x + col as f32 * cell;
+                let cy = y - (row + 1) as f32 * cell;
+
+                match letter {
+                    Some(letter) => {
+                        self.rect(cx, cy, cell, cell, line_width);
+                        if answers {
+                            let text = letter.to_string();
+                            let tx = cx + (cell - text_width(&text, Font::Roman, letter_size)) / 2.0;
+                            let ty = cy + (cell - letter_size * 0.66) / 2.0;
+                            self.text(tx, ty, Font::Roman, letter_size, &text);
+                        } else if let Some(num) = numbers[row][col] {
+                            let tx = cx + cell * 0.05;
+                            let ty = cy + cell * 0.95 - number_size * 0.8;
+                            self.text(tx, ty, Font::Roman, number_size, &num.to_string());
+                        }
+                    }
+                    None => self.fill_rect(cx, cy, cell, cell),
+                }
+            }
+        }
+    }
+}
+
+fn clue_column_height(clues: &[Clue], width: f32, size: f32) -> f32 {
+    let line_height = size * 1.2;
+    let label_width = text_width("00.", Font::Roman, size) + size * 0.5;
+    let lines: usize = clues.iter()
+        .map(|c| wrap_text(&c.clue, Font::Roman, size, width - label_width).len())
+        .sum();
+    SIZE_SUBSECTION * 1.6 + lines as f32 * line_height + clues.len() as f32 * size * 0.2
+}
+
+fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    let widths = font.widths();
+    let units: u32 = text.chars()
+        .map(|c| {
+            let code = c as u32;
+            if (32..127).contains(&code) {
+                widths[(code - 32) as usize] as u32
+            } else {
+                500
+            }
+        })
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+fn wrap_text(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    wrap_text_with(text, font, size, |_| width)
+}
+
+/// Greedy word wrap where the width may vary by line number
+fn wrap_text_with(text: &str, font: Font, size: f32, width_for_line: impl Fn(usize) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+
+        if current.is_empty() || text_width(&candidate, font, size) <= width_for_line(lines.len()) {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Escape text for a PDF literal string in WinAnsiEncoding
+fn pdf_string(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        let byte = match c {
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201C}' => 0x93,
+            '\u{201D}' => 0x94,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            c if (c as u32) < 0x100 => c as u32 as u8,
+            _ => b'?',
+        };
+        match byte {
+            b'(' | b')' | b'\\' => {
+                out.push('\\');
+                out.push(byte as char);
+            }
+            32..=126 => out.push(byte as char),
+            _ => out.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    out
+}
+
+/// Serialize pages into a PDF file with an uncompressed content stream per page
+fn write_pdf(pages: &[String], layout: &PageLayout, config: &BookConfig) -> Vec<u8> {
+    let mut objects: Vec<String> = Vec::new();
+
+    // 1: catalog, 2: page tree, 3-5: fonts, 6: info, then (page, content) pairs
+    let first_page_obj = 7;
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", first_page_obj + i * 2))
+        .collect();
+
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+    objects.push(format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        pages.len()
+    ));
+    for base_font in ["Times-Roman", "Times-Bold", "Times-Italic"] {
+        objects.push(format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+            base_font
+        ));
+    }
+
+    let mut info = format!("<< /Title ({}) /Producer (crossword-core)", pdf_string(&config.title));
+    if let Some(ref author) = config.author {
+        info.push_str(&format!(" /Author ({})", pdf_string(author)));
+    }
+    info.push_str(" >>");
+    objects.push(info);
+
+    for (idx, content) in pages.iter().enumerate() {
+        let content_obj = first_page_obj + idx * 2 + 1;
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+            layout.width, layout.height, content_obj
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (idx, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", idx + 1, object));
+    }
+
+    let xref_offset = pdf.len();
+    pdf.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+
+    pdf.into_bytes()
+}
+
+// Advance widths (1/1000 em) for ASCII 32..126 from the standard Adobe AFM files
+const TIMES_ROMAN_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 333, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+    921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+    556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+    333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+    500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+const TIMES_BOLD_WIDTHS: [u16; 95] = [
+    250, 333, 555, 500, 500, 1000, 833, 333, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    930, 722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778,
+    611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, 333, 278, 333, 581, 500,
+    333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500,
+    556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sample_puzzle;
+
+    #[test]
+    fn test_pdf_structure() {
+        let puzzle = sample_puzzle(3, |w| format!("Clue for {}", w));
+
+        let mut config = BookConfig::new("Test (Book)".to_string(), 3);
+        config.author = Some("Jane Doe".to_string());
+        let mut book = CrosswordBook::new(config);
+        book.add_puzzle(puzzle.clone());
+
+        let pdf = PdfGenerator::new().generate_document(&book).unwrap();
+        let text = String::from_utf8(pdf).unwrap();
+
+        // 4 front matter + intro + clue + grid + blank + answer key
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Count 9"));
+        assert!(text.contains("(Test \\(Book\\)) Tj"));
+        assert!(text.contains("(Puzzle 1) Tj"));
+
+        // Every xref offset points at its object header
+        let xref = text.rfind("xref\n").unwrap();
+        for (idx, line) in text[xref..].lines().skip(3).take_while(|l| l.ends_with(" n ")).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", idx + 1)));
+        }
+
+        // A section opens on a heading page of its own and heads its answers
+        let mut config = BookConfig::new("Sections".to_string(), 3);
+        config.sections = vec![Section { title: "Warm-up".to_string(), intro: Some("Easy ones".to_string()), grid_size: 3 }];
+        let mut book = CrosswordBook::new(config);
+        book.add_section_puzzle(0, puzzle);
+        let text = String::from_utf8(PdfGenerator::new().generate_document(&book).unwrap()).unwrap();
+        // 4 front matter + intro + blank + opener + clue + grid + blank + answer key
+        assert!(text.contains("/Count 11"));
+        assert_eq!(text.matches("(Warm-up) Tj").count(), 2);
+        assert!(text.contains("(Warm-up \\(1\\)) Tj"));
+        assert!(text.contains("(Easy ones) Tj"));
+    }
+
+    #[test]
+    fn test_pdf_overflow() {
+        // Clues that do not fit at the smallest size continue on more pages
+        let mut puzzle = sample_puzzle(3, |w| format!("Clue for {}", w));
+        puzzle.across_clues = vec![puzzle.across_clues[0].clone(); 200];
+
+        let mut config = BookConfig::new("Overflow".to_string(), 3);
+        config.sections = vec![Section { title: "Long".to_string(), intro: Some("An intro line\n\n".repeat(100)), grid_size: 3 }];
+        let mut book = CrosswordBook::new(config);
+        book.add_section_puzzle(0, puzzle);
+
+        let text = String::from_utf8(PdfGenerator::new().generate_document(&book).unwrap()).unwrap();
+        assert_eq!(text.matches("(Clue for CAT) Tj").count(), 200);
+        assert_eq!(text.matches("(Clue for CAB) Tj").count(), 1);
+        assert!(text.contains("(Puzzle 1 \\(continued\\)) Tj"));
+
+        // A long section intro wraps onto more pages instead of being cut
+        assert_eq!(text.matches("(An intro line) Tj").count(), 100);
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        let lines = wrap_text("the quick brown fox jumps over the lazy dog", Font::Roman, 10.0, 60.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| text_width(l, Font::Roman, 10.0) <= 60.0));
+        assert_eq!(lines.join(" "), "the quick brown fox jumps over the lazy dog");
+    }
+}
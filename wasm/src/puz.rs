@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use crate::solution::CrosswordPuzzle;
+
+const MAGIC: &[u8] = b"ACROSS&DOWN\0";
+const HEADER_LEN: usize = 0x34;
+const SCRAMBLED_FLAG: u16 = 0x0004;
+
+/// A puzzle read from an Across Lite `.puz` file, with the text fields
+/// that `CrosswordPuzzle` has no place for.
+#[derive(Debug, Clone)]
+pub struct PuzFile {
+    pub title: String,
+    pub author: String,
+    pub copyright: String,
+    pub notes: String,
+    pub puzzle: CrosswordPuzzle,
+}
+
+/// Parse an Across Lite `.puz` file, verifying all of its checksums.
+pub fn parse_puz(data: &[u8]) -> Result<PuzFile, String> {
+    // Some files carry a preamble before the header, so locate the magic
+    let magic_pos = data.windows(MAGIC.len())
+        .position(|w| w == MAGIC)
+        .ok_or_else(|| "Not a .puz file (missing ACROSS&DOWN magic)".to_string())?;
+
+    if magic_pos < 2 {
+        return Err("Truncated .puz header".to_string());
+    }
+
+    let data = &data[magic_pos - 2..];
+    if data.len() < HEADER_LEN {
+        return Err("Truncated .puz header".to_string());
+    }
+
+    let file_checksum = read_u16(data, 0x00);
+    let cib_checksum = read_u16(data, 0x0E);
+    let masked_low = &data[0x10..0x14];
+    let masked_high = &data[0x14..0x18];
+    let version = String::from_utf8_lossy(&data[0x18..0x1B]).to_string();
+    let width = data[0x2C] as usize;
+    let height = data[0x2D] as usize;
+    let num_clues = read_u16(data, 0x2E) as usize;
+    let scrambled = read_u16(data, 0x32);
+
+    if width == 0 || height == 0 {
+        return Err("Empty .puz grid".to_string());
+    }
+
+    if width != height {
+        return Err(format!("Only square grids are supported (got {}x{})", width, height));
+    }
+
+    if scrambled & SCRAMBLED_FLAG != 0 {
+        return Err("Scrambled .puz files are not supported".to_string());
+    }
+
+    let cells = width * height;
+    if data.len() < HEADER_LEN + 2 * cells {
+        return Err("Truncated .puz grid".to_string());
+    }
+
+    let solution = &data[HEADER_LEN..HEADER_LEN + cells];
+    let player_grid = &data[HEADER_LEN + cells..HEADER_LEN + 2 * cells];
+
+    // Strings: title, author, copyright, clues..., notes (all NUL-terminated)
+    let utf8 = version.as_str() >= "2.0";
+    let mut strings = data[HEADER_LEN + 2 * cells..].split(|&b| b == 0);
+    let mut next_string = |what: &str| -> Result<String, String> {
+        strings.next()
+            .map(|s| decode_text(s, utf8))
+            .ok_or_else(|| format!("Truncated .puz file: missing {}", what))
+    };
+
+    let title = next_string("title")?;
+    let author = next_string("author")?;
+    let copyright = next_string("copyright")?;
+    let mut clues = Vec::with_capacity(num_clues);
+    for i in 0..num_clues {
+        clues.push(next_string(&format!("clue {}", i + 1))?);
+    }
+    let notes = next_string("notes").unwrap_or_default();
+
+    // Checksums
+    let computed_cib = checksum_region(&data[0x2C..HEADER_LEN], 0);
+    if computed_cib != cib_checksum {
+        return Err(format!("CIB checksum mismatch (expected {:04x}, got {:04x})", cib_checksum, computed_cib));
+    }
+
+    let mut computed_file = computed_cib;
+    computed_file = checksum_region(solution, computed_file);
+    computed_file = checksum_region(player_grid, computed_file);
+    computed_file = checksum_strings(&data[HEADER_LEN + 2 * cells..], num_clues, &version, computed_file);
+    if computed_file != file_checksum {
+        return Err(format!("File checksum mismatch (expected {:04x}, got {:04x})", file_checksum, computed_file));
+    }
+
+    let solution_checksum = checksum_region(solution, 0);
+    let grid_checksum = checksum_region(player_grid, 0);
+    let strings_checksum = checksum_strings(&data[HEADER_LEN + 2 * cells..], num_clues, &version, 0);
+    let (expected_low, expected_high) = masked_checksums(cib_checksum, solution_checksum, grid_checksum, strings_checksum);
+    if masked_low != expected_low || masked_high != expected_high {
+        return Err("Masked checksum mismatch".to_string());
+    }
+
+    // Grid: '.' is a black square, anything else is a letter
+    let grid: Vec<Vec<Option<char>>> = solution
+        .chunks(width)
+        .map(|row| {
+            row.iter()
+                .map(|&b| if b == b'.' { None } else { Some((b as char).to_ascii_uppercase()) })
+                .collect()
+        })
+        .collect();
+
+    // Clues are stored in cell-number order, across before down
+    let placements = CrosswordPuzzle::placements_from_grid(&grid);
+    if placements.len() != num_clues {
+        return Err(format!("Grid has {} entries but file has {} clues", placements.len(), num_clues));
+    }
+
+    let clue_map: HashMap<(usize, usize, bool), String> = placements.iter()
+        .zip(clues)
+        .map(|(p, clue)| ((p.x, p.y, p.horizontal), clue))
+        .collect();
+
+    let mut puzzle = CrosswordPuzzle::from_placements(&placements, width, |_| String::new(), 0);
+    for clue in &mut puzzle.across_clues {
+        clue.clue = clue_map[&(clue.x, clue.y, true)].clone();
+    }
+    for clue in &mut puzzle.down_clues {
+        clue.clue = clue_map[&(clue.x, clue.y, false)].clone();
+    }
+
+    Ok(PuzFile {
+        title,
+        author,
+        copyright,
+        notes,
+        puzzle,
+    })
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+/// Versions before 2.0 store text as ISO-8859-1, which maps byte-for-byte to chars
+fn decode_text(bytes: &[u8], utf8: bool) -> String {
+    if utf8 {
+        String::from_utf8_lossy(bytes).to_string()
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    }
+}
+
+fn checksum_region(data: &[u8], mut checksum: u16) -> u16 {
+    for &b in data {
+        checksum = if checksum & 1 != 0 {
+            (checksum >> 1).wrapping_add(0x8000)
+        } else {
+            checksum >> 1
+        };
+        checksum = checksum.wrapping_add(b as u16);
+    }
+    checksum
+}
+
+/// Checksum of the string section: title, author and copyright include their
+/// terminator (and are skipped when empty), clues do not, notes only from 1.3.
+fn checksum_strings(section: &[u8], num_clues: usize, version: &str, mut checksum: u16) -> u16 {
+    let mut strings = section.split(|&b| b == 0);
+
+    for _ in 0..3 {
+        if let Some(s) = strings.next() {
+            if !s.is_empty() {
+                checksum = checksum_region(s, checksum);
+                checksum = checksum_region(&[0], checksum);
+            }
+        }
+    }
+
+    for _ in 0..num_clues {
+        if let Some(s) = strings.next() {
+            checksum = checksum_region(s, checksum);
+        }
+    }
+
+    if version >= "1.3" {
+        if let Some(s) = strings.next() {
+            if !s.is_empty() {
+                checksum = checksum_region(s, checksum);
+                checksum = checksum_region(&[0], checksum);
+            }
+        }
+    }
+
+    checksum
+}
+
+/// The "ICHEATED" masked checksums stored at 0x10..0x18
+fn masked_checksums(cib: u16, solution: u16, grid: u16, strings: u16) -> ([u8; 4], [u8; 4]) {
+    let sums = [cib, solution, grid, strings];
+    let mut low = [0u8; 4];
+    let mut high = [0u8; 4];
+    for (i, sum) in sums.iter().enumerate() {
+        low[i] = b"ICHE"[i] ^ (sum & 0xFF) as u8;
+        high[i] = b"ATED"[i] ^ (sum >> 8) as u8;
+    }
+    (low, high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a 3x3 puzzle with a black centre square:
+    ///   CAT
+    ///   A.O
+    ///   BET
+    fn sample_puz() -> Vec<u8> {
+        let solution = b"CATA.OBET";
+        let player: Vec<u8> = solution.iter().map(|&b| if b == b'.' { b'.' } else { b'-' }).collect();
+        let clues = ["Feline", "Taxi", "Toddler", "Wager"];
+
+        let mut strings = Vec::new();
+        for s in ["Test Puzzle", "Tester", "(c) 2024"] {
+            strings.extend_from_slice(s.as_bytes());
+            strings.push(0);
+        }
+        for clue in clues {
+            strings.extend_from_slice(clue.as_bytes());
+            strings.push(0);
+        }
+        strings.push(0); // empty notes
+
+        let mut header = vec![0u8; HEADER_LEN];
+        header[0x02..0x0E].copy_from_slice(MAGIC);
+        header[0x18..0x1C].copy_from_slice(b"1.3\0");
+        header[0x2C] = 3;
+        header[0x2D] = 3;
+        header[0x2E..0x30].copy_from_slice(&(clues.len() as u16).to_le_bytes());
+        header[0x30..0x32].copy_from_slice(&1u16.to_le_bytes());
+
+        let cib = checksum_region(&header[0x2C..HEADER_LEN], 0);
+        let sol = checksum_region(solution, 0);
+        let grid = checksum_region(&player, 0);
+        let text = checksum_strings(&strings, clues.len(), "1.3", 0);
+        let mut file = checksum_region(solution, cib);
+        file = checksum_region(&player, file);
+        file = checksum_strings(&strings, clues.len(), "1.3", file);
+        let (low, high) = masked_checksums(cib, sol, grid, text);
+
+        header[0x00..0x02].copy_from_slice(&file.to_le_bytes());
+        header[0x0E..0x10].copy_from_slice(&cib.to_le_bytes());
+        header[0x10..0x14].copy_from_slice(&low);
+        header[0x14..0x18].copy_from_slice(&high);
+
+        let mut data = header;
+        data.extend_from_slice(solution);
+        data.extend_from_slice(&player);
+        data.extend_from_slice(&strings);
+        data
+    }
+
+    #[test]
+    fn test_parse_puz() {
+        let puz = parse_puz(&sample_puz()).unwrap();
+        assert_eq!(puz.title, "Test Puzzle");
+        assert_eq!(puz.author, "Tester");
+        assert_eq!(puz.puzzle.grid[1][1], None);
+        assert_eq!(puz.puzzle.grid[2][2], Some('T'));
+
+        let across: Vec<_> = puz.puzzle.across_clues.iter()
+            .map(|c| (c.number, c.word.as_str(), c.clue.as_str()))
+            .collect();
+        assert_eq!(across, vec![(1, "CAT", "Feline"), (3, "BET", "Wager")]);
+
+        let down: Vec<_> = puz.puzzle.down_clues.iter()
+            .map(|c| (c.number, c.word.as_str(), c.clue.as_str()))
+            .collect();
+        assert_eq!(down, vec![(1, "CAB", "Taxi"), (2, "TOT", "Toddler")]);
+    }
+
+    #[test]
+    fn test_parse_puz_rejects_bad_checksum() {
+        let mut data = sample_puz();
+        let last_clue = data.len() - 3;
+        data[last_clue] ^= 0x20;
+        assert!(parse_puz(&data).unwrap_err().contains("checksum"));
+    }
+}
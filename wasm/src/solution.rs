@@ -125,4 +125,51 @@ impl CrosswordPuzzle {
             },
         }
     }
+
+    /// Clue number of each cell that starts an entry. Clues placed outside
+    /// the grid are left out.
+    pub fn cell_numbers(&self) -> Vec<Vec<Option<usize>>> {
+        let size = self.grid.len();
+        let mut numbers = vec![vec![None; size]; size];
+        for clue in self.across_clues.iter().chain(&self.down_clues) {
+            if let Some(cell) = numbers.get_mut(clue.y).and_then(|row| row.get_mut(clue.x)) {
+                *cell = Some(clue.number);
+            }
+        }
+        numbers
+    }
+
+    /// Recover word placements from a filled grid, in clue order
+    /// (by cell number, across before down). Single-letter runs are not words.
+    pub fn placements_from_grid(grid: &[Vec<Option<char>>]) -> Vec<Placement> {
+        let height = grid.len();
+        let mut placements = Vec::new();
+
+        for y in 0..height {
+            let width = grid[y].len();
+            for x in 0..width {
+                if grid[y][x].is_none() {
+                    continue;
+                }
+
+                if x == 0 || grid[y][x - 1].is_none() {
+                    let word: String = grid[y][x..].iter().map_while(|c| *c).collect();
+                    if word.chars().count() >= 2 {
+                        placements.push(Placement { word, x, y, horizontal: true });
+                    }
+                }
+
+                if y == 0 || grid[y - 1][x].is_none() {
+                    let word: String = grid[y..].iter()
+                        .map_while(|row| row.get(x).copied().flatten())
+                        .collect();
+                    if word.chars().count() >= 2 {
+                        placements.push(Placement { word, x, y, horizontal: false });
+                    }
+                }
+            }
+        }
+
+        placements
+    }
 }
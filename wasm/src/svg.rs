@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use crate::solution::CrosswordPuzzle;
+
+/// Appearance settings for SVG grids. Sizes are in SVG user units (px).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SvgOptions {
+    pub cell_size: f32,
+    pub padding: f32,
+    pub line_width: f32,
+    pub border_width: f32,
+    pub font_family: String,
+    /// Clue number size as a fraction of the cell size
+    pub number_scale: f32,
+    /// Answer letter size as a fraction of the cell size
+    pub letter_scale: f32,
+    pub line_color: String,
+    pub block_color: String,
+    pub background_color: String,
+    pub text_color: String,
+    /// Fill for highlighted cells listed in `shaded_cells`
+    pub shade_color: String,
+    /// (x, y) cells to shade, e.g. themed entries
+    pub shaded_cells: Vec<(usize, usize)>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 32.0,
+            padding: 2.0,
+            line_width: 1.0,
+            border_width: 2.0,
+            font_family: "Helvetica, Arial, sans-serif".to_string(),
+            number_scale: 0.3,
+            letter_scale: 0.6,
+            line_color: "#000000".to_string(),
+            block_color: "#000000".to_string(),
+            background_color: "#ffffff".to_string(),
+            text_color: "#000000".to_string(),
+            shade_color: "#d9d9d9".to_string(),
+            shaded_cells: Vec::new(),
+        }
+    }
+}
+
+/// Render the blank puzzle grid with clue numbers
+pub fn render_puzzle_svg(puzzle: &CrosswordPuzzle, options: &SvgOptions) -> String {
+    render_svg(puzzle, options, false)
+}
+
+/// Render the filled answer grid
+pub fn render_solution_svg(puzzle: &CrosswordPuzzle, options: &SvgOptions) -> String {
+    render_svg(puzzle, options, true)
+}
+
+fn render_svg(puzzle: &CrosswordPuzzle, options: &SvgOptions, solution: bool) -> String {
+    let size = puzzle.grid.len();
+    let cell = options.cell_size;
+    let grid_size = cell * size as f32;
+    let total = grid_size + 2.0 * options.padding;
+    let origin = options.padding;
+
+    let mut svg = String::new();
+
+    svg.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\">\n",
+        fmt(total)
+    ));
+    svg.push_str(&format!(
+        "<rect x=\"0\" y=\"0\" width=\"{0}\" height=\"{0}\" fill=\"{1}\"/>\n",
+        fmt(total),
+        escape_attr(&options.background_color)
+    ));
+
+    // Cells
+    svg.push_str(&format!(
+        "<g stroke=\"{}\" stroke-width=\"{}\">\n",
+        escape_attr(&options.line_color),
+        fmt(options.line_width)
+    ));
+    for (row, cells) in puzzle.grid.iter().enumerate() {
+        for (col, letter) in cells.iter().enumerate() {
+            let fill = if letter.is_none() {
+                &options.block_color
+            } else if options.shaded_cells.contains(&(col, row)) {
+                &options.shade_color
+            } else {
+                &options.background_color
+            };
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" fill=\"{3}\"/>\n",
+                fmt(origin + col as f32 * cell),
+                fmt(origin + row as f32 * cell),
+                fmt(cell),
+                escape_attr(fill)
+            ));
+        }
+    }
+    svg.push_str("</g>\n");
+
+    // Numbers or letters
+    svg.push_str(&format!(
+        "<g font-family=\"{}\" fill=\"{}\">\n",
+        escape_attr(&options.font_family),
+        escape_attr(&options.text_color)
+    ));
+    if solution {
+        let font_size = cell * options.letter_scale;
+        for (row, cells) in puzzle.grid.iter().enumerate() {
+            for (col, letter) in cells.iter().enumerate() {
+                if let Some(letter) = letter {
+                    svg.push_str(&format!(
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                        fmt(origin + (col as f32 + 0.5) * cell),
+                        fmt(origin + (row as f32 + 0.55) * cell),
+                        fmt(font_size),
+                        escape_attr(&letter.to_string())
+                    ));
+                }
+            }
+        }
+    } else {
+        let font_size = cell * options.number_scale;
+        for (row, cells) in puzzle.cell_numbers().iter().enumerate() {
+            for (col, number) in cells.iter().enumerate() {
+                if let Some(number) = number {
+                    svg.push_str(&format!(
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\">{}</text>\n",
+                        fmt(origin + col as f32 * cell + cell * 0.08),
+                        fmt(origin + row as f32 * cell + font_size),
+                        fmt(font_size),
+                        number
+                    ));
+                }
+            }
+        }
+    }
+    svg.push_str("</g>\n");
+
+    // Outer border
+    svg.push_str(&format!(
+        "<rect x=\"{0}\" y=\"{0}\" width=\"{1}\" height=\"{1}\" fill=\"none\" stroke=\"{2}\" stroke-width=\"{3}\"/>\n",
+        fmt(origin),
+        fmt(grid_size),
+        escape_attr(&options.line_color),
+        fmt(options.border_width)
+    ));
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Format a coordinate without trailing zeros
+fn fmt(value: f32) -> String {
+    let s = format!("{:.2}", value);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn escape_attr(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sample_puzzle;
+
+    #[test]
+    fn test_render_puzzle_svg() {
+        let svg = render_puzzle_svg(&sample_puzzle(3, |_| String::new()), &SvgOptions::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("width=\"100\""));
+        assert_eq!(svg.matches("fill=\"#000000\"/>").count(), 4);
+        assert!(svg.contains(">1</text>"));
+        assert!(!svg.contains(">C</text>"));
+    }
+
+    #[test]
+    fn test_render_solution_svg() {
+        let options = SvgOptions {
+            shaded_cells: vec![(1, 0)],
+            ..Default::default()
+        };
+        let svg = render_solution_svg(&sample_puzzle(3, |_| String::new()), &options);
+        assert_eq!(svg.matches("</text>").count(), 5);
+        assert!(svg.contains(">C</text>"));
+        assert!(svg.contains("fill=\"#d9d9d9\""));
+    }
+}
@@ -0,0 +1,21 @@
+use crate::solution::{CrosswordPuzzle, Placement};
+
+/// CAT across and CAB down from the top-left corner of a `size` grid
+pub fn sample_puzzle(size: usize, clue: impl Fn(&str) -> String) -> CrosswordPuzzle {
+    let placements = vec![
+        Placement { word: "CAT".to_string(), x: 0, y: 0, horizontal: true },
+        Placement { word: "CAB".to_string(), x: 0, y: 0, horizontal: false },
+    ];
+    CrosswordPuzzle::from_placements(&placements, size, clue, 0)
+}
+
+/// The 3x3 sample with TOT down and BET across closing the ring
+pub fn filled_sample_puzzle(clue: impl Fn(&str) -> String) -> CrosswordPuzzle {
+    let placements = vec![
+        Placement { word: "CAT".to_string(), x: 0, y: 0, horizontal: true },
+        Placement { word: "CAB".to_string(), x: 0, y: 0, horizontal: false },
+        Placement { word: "TOT".to_string(), x: 2, y: 0, horizontal: false },
+        Placement { word: "BET".to_string(), x: 0, y: 2, horizontal: true },
+    ];
+    CrosswordPuzzle::from_placements(&placements, 3, clue, 0)
+}
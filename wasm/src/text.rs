@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use crate::solution::{Clue, CrosswordPuzzle};
+
+/// Character set used to draw text grids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextStyle {
+    /// Unicode box-drawing characters
+    Unicode,
+    /// Plain `+`, `-`, `|` and `#`, safe for any mail client
+    Ascii,
+    /// Unicode box drawing with ANSI colors for terminals
+    Ansi,
+}
+
+impl std::str::FromStr for TextStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unicode" => Ok(TextStyle::Unicode),
+            "ascii" => Ok(TextStyle::Ascii),
+            "ansi" => Ok(TextStyle::Ansi),
+            other => Err(format!("Invalid text style: {}. Use unicode, ascii or ansi", other)),
+        }
+    }
+}
+
+/// Settings for plain-text grids and clue lists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextOptions {
+    pub style: TextStyle,
+    /// Append the numbered across and down clue lists
+    pub clues: bool,
+    /// Wrap clue lines at this many columns (0 disables wrapping)
+    pub wrap_width: usize,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            style: TextStyle::Unicode,
+            clues: true,
+            wrap_width: 72,
+        }
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_BLOCK: &str = "\x1b[7m";
+const ANSI_NUMBER: &str = "\x1b[2m";
+const ANSI_LETTER: &str = "\x1b[1;36m";
+const ANSI_HEADING: &str = "\x1b[1m";
+
+/// Width of a cell interior; fits clue numbers up to 999
+const CELL_WIDTH: usize = 3;
+
+/// Render the blank puzzle grid with clue numbers, followed by the clues
+pub fn render_puzzle_text(puzzle: &CrosswordPuzzle, options: &TextOptions) -> String {
+    render_text(puzzle, options, false)
+}
+
+/// Render the filled answer grid, followed by the answers
+pub fn render_solution_text(puzzle: &CrosswordPuzzle, options: &TextOptions) -> String {
+    render_text(puzzle, options, true)
+}
+
+struct BoxChars {
+    horizontal: &'static str,
+    vertical: &'static str,
+    // Corners and junctions, indexed [top, middle, bottom][left, middle, right]
+    joints: [[&'static str; 3]; 3],
+    block: &'static str,
+}
+
+const UNICODE_CHARS: BoxChars = BoxChars {
+    horizontal: "─",
+    vertical: "│",
+    joints: [["┌", "┬", "┐"], ["├", "┼", "┤"], ["└", "┴", "┘"]],
+    block: "█",
+};
+
+const ASCII_CHARS: BoxChars = BoxChars {
+    horizontal: "-",
+    vertical: "|",
+    joints: [["+", "+", "+"], ["+", "+", "+"], ["+", "+", "+"]],
+    block: "#",
+};
+
+fn render_text(puzzle: &CrosswordPuzzle, options: &TextOptions, solution: bool) -> String {
+    let chars = match options.style {
+        TextStyle::Ascii => &ASCII_CHARS,
+        TextStyle::Unicode | TextStyle::Ansi => &UNICODE_CHARS,
+    };
+    let ansi = options.style == TextStyle::Ansi;
+    let numbers = puzzle.cell_numbers();
+    let columns = puzzle.grid.first().map_or(0, |row| row.len());
+
+    let mut text = String::new();
+    for (row, cells) in puzzle.grid.iter().enumerate() {
+        let joint_row = if row == 0 { 0 } else { 1 };
+        text.push_str(&rule(chars, chars.joints[joint_row], columns));
+
+        for (col, letter) in cells.iter().enumerate() {
+            text.push_str(chars.vertical);
+            match letter {
+                None if ansi => {
+                    text.push_str(ANSI_BLOCK);
+                    text.push_str(&" ".repeat(CELL_WIDTH));
+                    text.push_str(ANSI_RESET);
+                }
+                None => text.push_str(&chars.block.repeat(CELL_WIDTH)),
+                Some(letter) if solution => {
+                    let letter = format!(" {} ", letter);
+                    if ansi {
+                        text.push_str(&format!("{}{}{}", ANSI_LETTER, letter, ANSI_RESET));
+                    } else {
+                        text.push_str(&letter);
+                    }
+                }
+                Some(_) => {
+                    let label = numbers[row][col].map(|n| n.to_string()).unwrap_or_default();
+                    let label = format!("{:<width$}", label, width = CELL_WIDTH);
+                    if ansi {
+                        text.push_str(&format!("{}{}{}", ANSI_NUMBER, label, ANSI_RESET));
+                    } else {
+                        text.push_str(&label);
+                    }
+                }
+            }
+        }
+        text.push_str(chars.vertical);
+        text.push('\n');
+    }
+    if !puzzle.grid.is_empty() {
+        text.push_str(&rule(chars, chars.joints[2], columns));
+    }
+
+    if options.clues {
+        for (heading, clues) in [("ACROSS", &puzzle.across_clues), ("DOWN", &puzzle.down_clues)] {
+            text.push('\n');
+            if ansi {
+                text.push_str(&format!("{}{}{}\n", ANSI_HEADING, heading, ANSI_RESET));
+            } else {
+                text.push_str(heading);
+                text.push('\n');
+            }
+            for clue in clues.iter() {
+                text.push_str(&clue_line(clue, solution, options.wrap_width));
+            }
+        }
+    }
+
+    text
+}
+
+fn rule(chars: &BoxChars, joints: [&str; 3], columns: usize) -> String {
+    let segment = chars.horizontal.repeat(CELL_WIDTH);
+    let mut line = String::from(joints[0]);
+    for col in 0..columns {
+        if col > 0 {
+            line.push_str(joints[1]);
+        }
+        line.push_str(&segment);
+    }
+    line.push_str(joints[2]);
+    line.push('\n');
+    line
+}
+
+/// "  1. Clue text (5)", wrapped with a hanging indent. Solutions list the answer instead.
+fn clue_line(clue: &Clue, solution: bool, wrap_width: usize) -> String {
+    let prefix = format!("{:>3}. ", clue.number);
+    let body = if solution {
+        clue.word.clone()
+    } else {
+        format!("{} ({})", clue.clue, clue.word.chars().count())
+    };
+
+    let indent = " ".repeat(prefix.len());
+    let mut out = String::new();
+    let mut line = prefix;
+    let mut line_len = line.len();
+    let mut first_word = true;
+
+    for word in body.split_whitespace() {
+        let word_len = word.chars().count();
+        if !first_word && wrap_width > 0 && line_len + 1 + word_len > wrap_width {
+            out.push_str(line.trim_end());
+            out.push('\n');
+            line = indent.clone();
+            line_len = indent.len();
+            first_word = true;
+        }
+        if !first_word {
+            line.push(' ');
+            line_len += 1;
+        }
+        line.push_str(word);
+        line_len += word_len;
+        first_word = false;
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sample_puzzle;
+
+    #[test]
+    fn test_render_ascii_puzzle() {
+        let options = TextOptions { style: TextStyle::Ascii, ..Default::default() };
+        let text = render_puzzle_text(&sample_puzzle(3, |w| format!("Clue for {}", w)), &options);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "+---+---+---+");
+        assert_eq!(lines[1], "|1  |   |   |");
+        assert_eq!(lines[3], "|   |###|###|");
+        assert_eq!(lines[6], "+---+---+---+");
+        assert!(text.contains("ACROSS\n  1. Clue for CAT (3)\n"));
+        assert!(text.is_ascii());
+    }
+
+    #[test]
+    fn test_render_solution_and_wrapping() {
+        let options = TextOptions { clues: false, ..Default::default() };
+        let text = render_solution_text(&sample_puzzle(3, |w| format!("Clue for {}", w)), &options);
+        assert!(text.starts_with("┌───┬───┬───┐\n│ C │ A │ T │\n├───┼"));
+        assert!(text.ends_with("└───┴───┴───┘\n"));
+        assert!(!text.contains("ACROSS"));
+
+        let clue = Clue {
+            number: 12,
+            word: "CAT".to_string(),
+            clue: "A small domesticated carnivorous mammal".to_string(),
+            x: 0,
+            y: 0,
+        };
+        assert_eq!(
+            clue_line(&clue, false, 24),
+            " 12. A small\n     domesticated\n     carnivorous mammal\n     (3)\n"
+        );
+    }
+}
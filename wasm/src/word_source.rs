@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Clue text when no source has a clue for a word
+pub const MISSING_CLUE: &str = "Definition not available";
+
+/// Longest answer any source offers
+const MAX_WORD_LENGTH: usize = 15;
+
+/// Usage labels a definition can carry, each with the spellings that mean it
+const USAGE_LABELS: &[(&str, &[&str])] = &[
+    ("archaic", &["archaic", "arch"]),
+    ("colloq", &["colloq", "colloquial"]),
+    ("derog", &["derog", "derogatory"]),
+    ("joc", &["joc", "jocular"]),
+    ("poet", &["poet", "poetic"]),
+    ("literary", &["literary"]),
+    ("formal", &["formal"]),
+    ("slang", &["slang", "sl"]),
+    ("coarse", &["coarse"]),
+    ("offens", &["offens", "offensive"]),
+    ("hist", &["hist", "historical"]),
+    ("dial", &["dial", "dialect"]),
+    ("euphem", &["euphem", "euphemistic"]),
+    ("rhet", &["rhet", "rhetorical"]),
+];
+
+/// The short form of a usage label, e.g. "derog" for "Derogatory" or "derog."
+pub fn canonical_label(label: &str) -> Option<&'static str> {
+    let label = label.trim().trim_end_matches('.').to_lowercase();
+    USAGE_LABELS.iter()
+        .find(|(_, spellings)| spellings.contains(&label.as_str()))
+        .map(|(canonical, _)| *canonical)
+}
+
+/// Every label `canonical_label` knows, in short form
+pub fn known_labels() -> Vec<&'static str> {
+    USAGE_LABELS.iter().map(|(canonical, _)| *canonical).collect()
+}
+
+/// Supplies answer words and their clues. `Dictionary` wraps one and adds
+/// allowlist filtering and the missing-clue fallback.
+pub trait WordSource: Send + Sync {
+    /// Candidate answers: uppercase, A-Z only, sorted
+    fn words(&self) -> Vec<String>;
+
+    /// Clue for an uppercase word, or `None` when the source has none
+    fn clue(&self, word: &str) -> Option<String>;
+
+    /// Every clue the source has for an uppercase word, `clue` first.
+    /// Sources with one clue per word can keep the default.
+    fn clues(&self, word: &str) -> Vec<String> {
+        self.clue(word).into_iter().collect()
+    }
+
+    /// Usage labels of an uppercase word's entry, e.g. "archaic" or
+    /// "derog", in the short form `canonical_label` gives
+    fn labels(&self, _word: &str) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Whether the source records usage labels at all
+    fn has_labels(&self) -> bool {
+        false
+    }
+}
+
+/// File formats a `WordSource` can be read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DictionaryFormat {
+    /// `Word  definition` lines, as in the Oxford English Dictionary text
+    Oxford,
+    /// One word per line, no clues
+    WordList,
+    /// `WORD<TAB>clue` lines
+    Tsv,
+}
+
+impl DictionaryFormat {
+    /// Guess the format from the first lines of a file
+    pub fn detect(text: &str) -> Self {
+        let sample: Vec<&str> = content_lines(text).take(50).collect();
+        if sample.iter().any(|line| line.contains('\t')) {
+            DictionaryFormat::Tsv
+        } else if sample.iter().filter(|line| line.contains("  ")).count() * 2 > sample.len() {
+            DictionaryFormat::Oxford
+        } else {
+            DictionaryFormat::WordList
+        }
+    }
+
+    pub fn parse(self, text: &str) -> Box<dyn WordSource> {
+        match self {
+            DictionaryFormat::Oxford => Box::new(crate::dictionary::OxfordSource::parse(text)),
+            DictionaryFormat::WordList => Box::new(WordList::parse(text)),
+            DictionaryFormat::Tsv => Box::new(ClueTable::parse(text)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DictionaryFormat::Oxford => "oxford",
+            DictionaryFormat::WordList => "words",
+            DictionaryFormat::Tsv => "tsv",
+        }
+    }
+}
+
+impl std::str::FromStr for DictionaryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "oxford" => Ok(DictionaryFormat::Oxford),
+            "words" | "wordlist" => Ok(DictionaryFormat::WordList),
+            "tsv" => Ok(DictionaryFormat::Tsv),
+            other => Err(format!("Invalid dictionary format: {}. Use oxford, words or tsv", other)),
+        }
+    }
+}
+
+/// Read a word source from a file, detecting the format unless given
+pub fn load_word_source(path: &Path, format: Option<DictionaryFormat>) -> Result<(Box<dyn WordSource>, DictionaryFormat), String> {
+    let data = std::fs::read(path)
+        .map_err(|e| format!("Failed to read dictionary {}: {}", path.display(), e))?;
+    let text = String::from_utf8_lossy(&data);
+    let format = format.unwrap_or_else(|| DictionaryFormat::detect(&text));
+    Ok((format.parse(&text), format))
+}
+
+/// A plain word list. Every word is an answer; none has a clue.
+#[derive(Debug, Clone, Default)]
+pub struct WordList {
+    words: Vec<String>,
+}
+
+impl WordList {
+    pub fn parse(text: &str) -> Self {
+        let mut words: Vec<String> = content_lines(text).filter_map(normalize_word).collect();
+        words.sort();
+        words.dedup();
+        Self { words }
+    }
+}
+
+impl WordSource for WordList {
+    fn words(&self) -> Vec<String> {
+        self.words.clone()
+    }
+
+    fn clue(&self, _word: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Answers with their clues, one `WORD<TAB>clue` per line. A word may have
+/// several lines; the first is its main clue.
+#[derive(Debug, Clone, Default)]
+pub struct ClueTable {
+    clues: HashMap<String, Vec<String>>,
+}
+
+impl ClueTable {
+    pub fn parse(text: &str) -> Self {
+        let mut clues: HashMap<String, Vec<String>> = HashMap::new();
+        for line in content_lines(text) {
+            let Some((word, clue)) = line.split_once('\t') else { continue };
+            let clue = clue.trim();
+            if let (Some(word), false) = (normalize_word(word), clue.is_empty()) {
+                let senses = clues.entry(word).or_default();
+                if !senses.iter().any(|sense| sense == clue) {
+                    senses.push(clue.to_string());
+                }
+            }
+        }
+        Self { clues }
+    }
+}
+
+impl WordSource for ClueTable {
+    fn words(&self) -> Vec<String> {
+        let mut words: Vec<String> = self.clues.keys().cloned().collect();
+        words.sort();
+        words
+    }
+
+    fn clue(&self, word: &str) -> Option<String> {
+        self.clues.get(word).and_then(|senses| senses.first()).cloned()
+    }
+
+    fn clues(&self, word: &str) -> Vec<String> {
+        self.clues.get(word).cloned().unwrap_or_default()
+    }
+}
+
+/// Non-blank lines that aren't `#` comments
+fn content_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+}
+
+/// Uppercase answer with spaces and hyphens removed, if it's usable in a grid
+pub(crate) fn normalize_word(word: &str) -> Option<String> {
+    let word: String = word.trim().chars().filter(|c| *c != ' ' && *c != '-').collect::<String>().to_uppercase();
+    let usable = (3..=MAX_WORD_LENGTH).contains(&word.len()) && word.chars().all(|c| c.is_ascii_uppercase());
+    usable.then_some(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_sources() {
+        let list = WordList::parse("# pets\ncat\nDog\n\nice cream\nox\ncat\n");
+        assert_eq!(list.words(), ["CAT", "DOG", "ICECREAM"]);
+        assert_eq!(list.clue("CAT"), None);
+
+        let table = ClueTable::parse("CAT\tPurring pet\ncat\tSecond clue\nOWL\t\nX-RAY\tScan\n");
+        assert_eq!(table.words(), ["CAT", "XRAY"]);
+        assert_eq!(table.clue("CAT").as_deref(), Some("Purring pet"));
+        assert_eq!(table.clues("CAT"), ["Purring pet", "Second clue"]);
+
+        assert_eq!(DictionaryFormat::detect("CAT\tPet\n"), DictionaryFormat::Tsv);
+        assert_eq!(DictionaryFormat::detect("Cat  n. A small pet.\nDog  n. A loyal pet.\n"), DictionaryFormat::Oxford);
+        assert_eq!(DictionaryFormat::detect("CAT\nDOG\n"), DictionaryFormat::WordList);
+        assert!("csv".parse::<DictionaryFormat>().is_err());
+
+        assert_eq!(canonical_label("Derogatory"), Some("derog"));
+        assert_eq!(canonical_label("derog."), Some("derog"));
+        assert_eq!(canonical_label("sl."), Some("slang"));
+        assert_eq!(canonical_label("esp."), None);
+    }
+}